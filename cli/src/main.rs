@@ -1,4 +1,11 @@
-use clap::Parser;
+use clap::{ArgEnum, CommandFactory, FromArgMatches, Parser, Subcommand};
+use std::ffi::OsString;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::time::Instant;
+use zp_lib::{
+    compression_name, parse_crc32, parse_escapes, parse_manifest, parse_size, parse_utc_offset,
+    supported_compression_methods, Encoding, SummaryOptions, TreeOptions, TreeStyle, Zip,
+};
 
 /// Zip Parser
 #[derive(Parser)]
@@ -8,27 +15,907 @@ struct Args {
     #[clap(short, parse(from_occurrences))]
     verbose: u8,
 
-    /// One or more zip files
+    /// Output format (default: summary, or verbose with `-v`)
+    #[clap(long, arg_enum)]
+    format: Option<Format>,
+
+    /// Summarize only items with at least this uncompressed size (e.g. `512`, `64K`, `1M`)
+    #[clap(long, parse(try_from_str = parse_size))]
+    min_size: Option<u64>,
+
+    /// Summarize only items with at most this uncompressed size (e.g. `512`, `64K`, `1M`)
+    #[clap(long, parse(try_from_str = parse_size))]
+    max_size: Option<u64>,
+
+    /// Append the CRC-32 verification status (OK, BAD, or -) of each item to the summary
+    #[clap(long)]
+    verify: bool,
+
+    /// With `--verify`, decompress compressed items and check the CRC-32 of the decompressed data
+    /// (slower); otherwise only stored items are checked
+    #[clap(long)]
+    deep: bool,
+
+    /// Print the zip file comment, if any, before the summary; each line is prefixed with `# `
+    #[clap(long)]
+    show_comment: bool,
+
+    /// Separate summary fields with DELIM instead of a tab; `\0`, `\t`, `\n`, `\r`, and `\\` are
+    /// unescaped (e.g. `\0` for data that may contain tabs)
+    #[clap(long, value_name = "DELIM", parse(try_from_str = parse_escapes))]
+    delimiter: Option<String>,
+
+    /// Terminate summary rows, and separate verbose records, with SEP instead of a newline and
+    /// `---`; unescaped like `--delimiter` (e.g. `\0` for `xargs -0`)
+    #[clap(long, value_name = "SEP", parse(try_from_str = parse_escapes))]
+    separator: Option<String>,
+
+    /// Summarize only items with this CRC-32, in hex with or without `0x` (e.g. `d87f7e0c`)
+    #[clap(long, value_name = "HEX", parse(try_from_str = parse_crc32))]
+    crc: Option<u32>,
+
+    /// Summarize only items with exactly this uncompressed size (e.g. `512`, `64K`)
+    #[clap(long, value_name = "N", parse(try_from_str = parse_size))]
+    size: Option<u64>,
+
+    /// Summarize only items whose name matches GLOB (`?` and `*` match within a folder, `**`
+    /// across folders; e.g. `**/*.txt`); repeat to match any of several patterns
+    #[clap(long, value_name = "GLOB")]
+    filter: Vec<String>,
+
+    /// Leave out items whose name matches GLOB (like `--filter`), even if they match `--filter`;
+    /// repeat to exclude several patterns (e.g. `node_modules/**`)
+    #[clap(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Show summary modified dates in UTC with a `Z` suffix, assuming the stored MS-DOS date/times
+    /// (local time with no time zone) were recorded in UTC
+    #[clap(long, conflicts_with = "local-tz")]
+    assume_utc: bool,
+
+    /// Show summary modified dates in UTC with a `Z` suffix, assuming the stored MS-DOS date/times
+    /// were recorded at this UTC offset (`+HH:MM` or `-HH:MM`, e.g. `-05:00`)
+    #[clap(long, value_name = "OFFSET", parse(try_from_str = parse_utc_offset))]
+    local_tz: Option<i32>,
+
+    /// Order the summary depth-first by path, each folder followed by its contents
+    #[clap(long)]
+    tree_order: bool,
+
+    /// Append the compression method name (e.g. stored, deflated, or AES for AES-encrypted items)
+    /// to the summary
+    #[clap(long)]
+    show_method: bool,
+
+    /// Append the encryption method (none, ZipCrypto, AES-<bits>, or strong) to the summary
+    #[clap(long)]
+    show_encryption: bool,
+
+    /// Append the compression method name, compressed size, and uncompressed size from each
+    /// item's local file header (`-` if missing) to the summary, to compare them with the central
+    /// directory without running `--audit`
+    #[clap(long)]
+    detailed: bool,
+
+    /// Append `(exec)` to the summary of items with a Unix execute bit set, e.g. to review the
+    /// scripts and binaries in a zip file
+    #[clap(long)]
+    show_exec: bool,
+
+    /// Tree branch characters: unicode or ascii
+    #[clap(long, default_value = "unicode")]
+    tree_style: TreeStyle,
+
+    /// Append the uncompressed size of each file to the tree
+    #[clap(long)]
+    tree_sizes: bool,
+
+    /// Append the total uncompressed size of the files under each folder to the tree
+    #[clap(long)]
+    tree_folder_sizes: bool,
+
+    /// Append the modified date/time of each item to the tree
+    #[clap(long)]
+    tree_dates: bool,
+
+    /// Append `exec` to the tree entry of files with a Unix execute bit set
+    #[clap(long)]
+    tree_exec: bool,
+
+    /// Omit the trailing `---`, `EOF`, `---` lines from the verbose output, e.g. to concatenate
+    /// the output of several zip files
+    #[clap(long)]
+    no_eof_marker: bool,
+
+    /// Indent the JSON (and SBOM) output instead of printing it on a single line
+    #[clap(long)]
+    json_pretty: bool,
+
+    /// Print a single summary line per zip file and continue past errors
+    #[clap(
+        long,
+        conflicts_with_all = &[
+            "count", "audit", "check", "stats", "totals", "merge", "format", "verbose",
+        ],
+    )]
+    oneline: bool,
+
+    /// Print only the number of files in each zip file, one line per zip file, and continue past
+    /// errors
+    #[clap(
+        long,
+        conflicts_with_all = &["audit", "check", "stats", "totals", "merge", "format", "verbose"],
+    )]
+    count: bool,
+
+    /// With `--count`, count folders too
+    #[clap(long)]
+    count_dirs: bool,
+
+    /// Compare central directory file headers with their local file headers
+    #[clap(
+        long,
+        conflicts_with_all = &["check", "stats", "totals", "merge", "format", "verbose"],
+    )]
+    audit: bool,
+
+    /// Check for interoperability problems (data descriptor flag and UTF-8 flag mismatches, a
+    /// central directory offset that does not point to the central directory, and central
+    /// directory file headers out of local file header offset order)
+    #[clap(long, conflicts_with_all = &["stats", "totals", "merge", "format", "verbose"])]
+    check: bool,
+
+    /// After listing each zip file, decompress and list the nested zip file item NAME; repeat to
+    /// descend further (up to `--max-depth` levels)
+    #[clap(long, value_name = "NAME")]
+    follow: Vec<String>,
+
+    /// Maximum number of `--follow` levels, and maximum folder depth of the tree (deeper items
+    /// are counted instead of listed), to bound the work on untrusted zip files
+    #[clap(long, value_name = "N", default_value = "16")]
+    max_depth: usize,
+
+    /// Parse concatenated zip files (e.g. `cat a.zip b.zip > ab.zip`) and print each listing; the
+    /// listings after the first are preceded by `--- zip N at offset OFFSET`
+    #[clap(long)]
+    multi: bool,
+
+    /// Print one combined summary of all zip files, each row prefixed with its zip file path and
+    /// `:` (e.g. `a.zip:folder/file.txt`), and continue past errors, which are printed as
+    /// `PATH: Error: ...` rows
+    #[clap(long, conflicts_with_all = &["format", "verbose"])]
+    merge: bool,
+
+    /// Print statistics (counts, sizes, compression savings, and record counts) for each zip file
+    #[clap(long, conflicts_with_all = &["merge", "format", "verbose"])]
+    stats: bool,
+
+    /// Print the combined compression savings of all zip files
+    #[clap(long, conflicts_with_all = &["merge", "format", "verbose"])]
+    totals: bool,
+
+    /// Print how long parsing and formatting took and how many bytes were read to stderr
+    #[clap(long)]
+    timing: bool,
+
+    /// Print the SHA-256 of each zip file to stderr, in `sha256sum` format; the bytes are hashed
+    /// while parsing instead of in a second read
+    #[clap(long, conflicts_with = "multi")]
+    hash: bool,
+
+    /// Reject any nonconformance (trailing data, invalid UTF-8 or, with `--encoding`, lossily
+    /// decoded names and comments, size/count mismatches, missing end of central directory
+    /// record)
+    #[clap(long)]
+    strict: bool,
+
+    /// Treat warnings as errors: print any lossily decoded file names, duplicate file names,
+    /// entry count mismatch, or compression ratios over 100 to stderr and fail, even though the
+    /// zip file parsed
+    #[clap(long)]
+    strict_warnings: bool,
+
+    /// Encoding of file names and comments: auto (UTF-8, or CP437 if not valid UTF-8), utf-8,
+    /// cp437, or a legacy encoding such as shift_jis, gbk, or euc-kr
+    #[clap(long, default_value = "auto")]
+    encoding: Encoding,
+
+    /// One or more zip files; a zip file named like a subcommand (e.g. `extra`) is read as the
+    /// subcommand if its arguments parse (otherwise it is listed, as in `zp extra`), so pass it as
+    /// `./extra` or after `--` to always list it
     files: Vec<String>,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(ArgEnum, Clone, Copy)]
+enum Format {
+    /// File name, whether item is a folder, uncompressed size, modified date/time, and comment
+    Summary,
+
+    /// Complete analysis of the zip file contents
+    Verbose,
+
+    /// Complete analysis of the zip file contents as indented blocks
+    Pretty,
+
+    /// Stable tab-separated listing of names, offsets, sizes, CRC-32s, and compression methods
+    Manifest,
+
+    /// Folder tree of the item names
+    Tree,
+
+    /// JSON array of the central directory file headers (compact unless `--json-pretty`)
+    Json,
+
+    /// Tab-separated list of file names with the absolute offset and length of their compressed
+    /// data
+    Ranges,
+
+    /// Versioned JSON inventory of the files with their sizes, CRC-32s, and SHA-256s for SBOM
+    /// tooling (compact unless `--json-pretty`)
+    Sbom,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Write the decompressed data of an item in a zip file to stdout
+    Cat {
+        /// Zip file
+        file: String,
+
+        /// Item name
+        name: String,
+    },
+
+    /// Print a hex and ASCII dump of a byte range of a file, or of the local file record of an
+    /// item in a zip file
+    Dump {
+        /// Start of the byte range (default: 0)
+        #[clap(long)]
+        offset: Option<u64>,
+
+        /// Length of the byte range (default: to the end of the file)
+        #[clap(long)]
+        len: Option<u64>,
+
+        /// Dump the local file header, file data, and data descriptor of the item NAME instead
+        #[clap(long, value_name = "NAME", conflicts_with_all = &["offset", "len"])]
+        entry: Option<String>,
+
+        /// File
+        file: String,
+    },
+
+    /// Print the extra fields of an item in a zip file
+    Extra {
+        /// Zip file
+        file: String,
+
+        /// Item name
+        name: String,
+    },
+
+    /// Extract the items of a zip file into a folder
+    Extract {
+        /// Print the path each item would be written to, without writing anything
+        #[clap(long)]
+        dry_run: bool,
+
+        /// Destination folder
+        #[clap(short, long, default_value = ".")]
+        dest: String,
+
+        /// Zip file
+        file: String,
+    },
+
+    /// Print an overview of a zip file: format, counts, sizes, compression methods, encryption,
+    /// required version, and comment
+    Info {
+        /// Zip file
+        file: String,
+    },
+
+    /// Print the file names in one or more zip files, one per line
+    Names {
+        /// One or more zip files
+        #[clap(required = true)]
+        files: Vec<String>,
+    },
+
+    /// List the largest files in a zip file: uncompressed size, compressed size, savings, and name
+    Top {
+        /// Zip file
+        file: String,
+
+        /// Number of files
+        #[clap(default_value = "10")]
+        count: usize,
+    },
+
+    /// Check the CRC-32 of each item in one or more zip files
+    Verify {
+        /// Decompress compressed items and check the CRC-32 of the decompressed data (slower);
+        /// otherwise only stored items are checked
+        #[clap(long)]
+        deep: bool,
+
+        /// One or more zip files
+        #[clap(required = true)]
+        files: Vec<String>,
+    },
+
+    /// Check that a zip file contains exactly the items in a manifest (as written by `--format
+    /// manifest`), with the same CRC-32s and uncompressed sizes
+    VerifyManifest {
+        /// Zip file
+        file: String,
+
+        /// Manifest: a header line of tab-separated column names including `name`, `crc32`
+        /// (decimal), and `uncompressed_size`, then a tab-separated line per item
+        manifest: String,
+    },
 }
 
 fn main() -> Result<(), String> {
-    let args = Args::parse();
+    // The supported compression methods depend on the features of zp-lib, so they are added to
+    // `--version` and `--help` at run time
+    let methods = format!(
+        "Supported compression methods: {}",
+        supported_compression_methods()
+            .iter()
+            .map(|&method| format!("{} ({method})", compression_name(method)))
+            .collect::<Vec<_>>()
+            .join(", "),
+    );
+    let long_version = format!("{}\n{methods}", env!("CARGO_PKG_VERSION"));
+    let command = Args::command()
+        .long_version(long_version.as_str())
+        .after_help(methods.as_str());
+    let argv = std::env::args_os().collect::<Vec<_>>();
+    let matches = command
+        .clone()
+        .try_get_matches_from(&argv)
+        .or_else(|e| match subcommand_file(&command, &argv) {
+            Some(argv) if e.use_stderr() => {
+                command.clone().try_get_matches_from(argv).map_err(|_| e)
+            }
+            _ => Err(e),
+        })
+        .unwrap_or_else(|e| e.exit());
+    let args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    if let Some(command) = &args.command {
+        return match command {
+            Command::Cat { file, name } => cat(file, name, &args),
+            Command::Dump {
+                offset,
+                len,
+                entry,
+                file,
+            } => dump(file, offset.unwrap_or(0), *len, entry.as_deref(), &args),
+            Command::Extra { file, name } => extra(file, name, &args),
+            Command::Extract {
+                dry_run,
+                dest,
+                file,
+            } => extract(file, dest, *dry_run, &args),
+            Command::Info { file } => info(file, &args),
+            Command::Names { files } => names(files, &args),
+            Command::Top { file, count } => top(file, *count, &args),
+            Command::Verify { deep, files } => verify(files, *deep, &args),
+            Command::VerifyManifest { file, manifest } => verify_manifest(file, manifest, &args),
+        };
+    }
     if args.files.len() < 1 {
         return Err(String::from(
             "No files provided. Run with `-h` to view usage.",
         ));
     }
-    let verbose = args.verbose > 0;
-    for i in args.files {
-        match zp_lib::process_file(&i, verbose) {
-            Ok(o) => {
-                println!("{o}");
+    if args.oneline {
+        return oneline(&args.files, &args);
+    }
+    if args.count {
+        return count(&args.files, &args);
+    }
+    if args.audit {
+        return audit(&args.files, &args);
+    }
+    if args.check {
+        return check(&args.files, &args);
+    }
+    if args.stats || args.totals {
+        return stats(&args.files, &args);
+    }
+    let format = match args.format {
+        Some(format) => format,
+        None if args.verbose > 0 => Format::Verbose,
+        None => Format::Summary,
+    };
+    let options = SummaryOptions {
+        comment: args.show_comment,
+        encryption: args.show_encryption,
+        method: args.show_method,
+        executable: args.show_exec,
+        verify: args.verify,
+        tree_order: args.tree_order,
+        deep: args.deep,
+        detailed: args.detailed,
+        min_size: args.min_size,
+        max_size: args.max_size,
+        crc32: args.crc,
+        size: args.size,
+        delimiter: args.delimiter.clone(),
+        separator: args.separator.clone(),
+        filter: args.filter.clone(),
+        exclude: args.exclude.clone(),
+        utc_offset: args
+            .local_tz
+            .or(if args.assume_utc { Some(0) } else { None }),
+    };
+    if args.merge {
+        return merge(&args.files, &options, &args);
+    }
+    let tree = TreeOptions {
+        style: args.tree_style,
+        sizes: args.tree_sizes,
+        folder_sizes: args.tree_folder_sizes,
+        dates: args.tree_dates,
+        executable: args.tree_exec,
+        max_depth: Some(args.max_depth),
+    };
+    if args.follow.len() > args.max_depth {
+        return Err(format!(
+            "Cannot follow more than {} nested zip files (see `--max-depth`)",
+            args.max_depth,
+        ));
+    }
+    let output = |zip: &Zip| match format {
+        Format::Summary => zip.summary_with(&options),
+        Format::Verbose if args.no_eof_marker => {
+            zip.verbose_without_eof(args.separator.as_deref().unwrap_or("---\n"))
+        }
+        Format::Verbose => zip.verbose_with(args.separator.as_deref().unwrap_or("---\n")),
+        Format::Pretty => zip.pretty(),
+        Format::Manifest => Ok(zip.manifest()),
+        Format::Tree => Ok(zip.tree_with(&tree)),
+        Format::Json => Ok(zip.json(args.json_pretty)),
+        Format::Ranges => Ok(zip.ranges()),
+        Format::Sbom => Ok(zip.sbom(args.json_pretty)),
+    };
+    // A custom separator already terminates the output
+    let end = if args.separator.is_some() { "" } else { "\n" };
+    for i in &args.files {
+        if args.multi {
+            for (n, zip) in open_multi(i, &args)?.iter().enumerate() {
+                if n > 0 {
+                    println!("--- zip {} at offset {}", n + 1, zip.base_offset());
+                }
+                print!("{}{end}", output(zip)?);
+            }
+            continue;
+        }
+        let start = Instant::now();
+        let (mut zip, bytes) = if args.timing {
+            open_counted(i, &args)?
+        } else {
+            (open(i, &args)?, 0)
+        };
+        let parsed = start.elapsed();
+        let o = output(&zip)?;
+        if args.timing {
+            eprintln!(
+                "{i}: read {bytes} bytes, parsed in {parsed:?}, formatted in {:?}",
+                start.elapsed() - parsed,
+            );
+        }
+        if let Some(digest) = zip.sha256() {
+            eprintln!("{digest}  {i}");
+        }
+        print!("{o}{end}");
+        let reader = Zip::reader().encoding(args.encoding).strict(args.strict);
+        for name in &args.follow {
+            zip = zip.nested_with(name, &reader)?;
+            escalate(&zip, name, &args)?;
+            println!("--- {name}");
+            print!("{}{end}", output(&zip)?);
+        }
+    }
+    Ok(())
+}
+
+/// Arguments with `--` inserted before the first subcommand name if a file of that name exists,
+/// to retry `zp extra` as listing the zip file `extra` when it fails as a subcommand
+fn subcommand_file(command: &clap::Command, argv: &[OsString]) -> Option<Vec<OsString>> {
+    let n = argv.iter().skip(1).position(|arg| {
+        command
+            .get_subcommands()
+            .any(|i| arg.to_str() == Some(i.get_name()))
+    })? + 1;
+    if !std::path::Path::new(&argv[n]).is_file() {
+        return None;
+    }
+    let mut argv = argv.to_vec();
+    argv.insert(n, OsString::from("--"));
+    Some(argv)
+}
+
+/// Reader that counts the bytes read through it, for `--timing`
+struct Counting<R> {
+    inner: R,
+    count: u64,
+}
+
+impl<R: Read> Read for Counting<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: Seek> Seek for Counting<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+/// Like [`open`], and also return the number of bytes read from the file, which exceeds its
+/// size if parts of it are read more than once
+fn open_counted(path: &str, args: &Args) -> Result<(Zip, u64), String> {
+    if !std::path::Path::new(path).is_file() {
+        // Fail with the same error as `open`
+        return open(path, args).map(|zip| (zip, 0));
+    }
+    let file = std::fs::File::open(path).map_err(|e| format!("{e}: `{path}`"))?;
+    let mut r = BufReader::new(Counting {
+        inner: file,
+        count: 0,
+    });
+    let zip = Zip::reader()
+        .encoding(args.encoding)
+        .strict(args.strict)
+        .hash(args.hash)
+        .process(&mut r)?;
+    escalate(&zip, path, args)?;
+    Ok((zip, r.get_ref().count))
+}
+
+/// Process a zip file at path, optionally rejecting any nonconformance and hashing it, and
+/// select the encoding
+fn open(path: &str, args: &Args) -> Result<Zip, String> {
+    let zip = Zip::reader()
+        .encoding(args.encoding)
+        .strict(args.strict)
+        .hash(args.hash)
+        .open(path)?;
+    escalate(&zip, path, args)?;
+    Ok(zip)
+}
+
+/// With `--strict-warnings`, print the warnings of a zip file to stderr and fail if there are any
+fn escalate(zip: &Zip, path: &str, args: &Args) -> Result<(), String> {
+    if !args.strict_warnings {
+        return Ok(());
+    }
+    let warnings = zip.warnings();
+    for warning in &warnings {
+        eprintln!("{path}: Warning: {warning}");
+    }
+    if !warnings.is_empty() {
+        return Err(format!("Found {} warnings in `{path}`", warnings.len()));
+    }
+    Ok(())
+}
+
+/// Like [`open`], but parse each of the concatenated zip files in the file
+fn open_multi(path: &str, args: &Args) -> Result<Vec<Zip>, String> {
+    let zips = Zip::reader()
+        .encoding(args.encoding)
+        .strict(args.strict)
+        .open_multi(path)?;
+    for zip in &zips {
+        escalate(zip, path, args)?;
+    }
+    Ok(zips)
+}
+
+/// Print one line per zip file; errors get their own line instead of aborting the batch
+fn oneline(files: &[String], args: &Args) -> Result<(), String> {
+    let mut failed = 0;
+    for i in files {
+        match open(i, args) {
+            Ok(zip) => {
+                println!("{i}: {}", zip.stats());
+            }
+            Err(e) => {
+                println!("{i}: Error: {e}");
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(format!("{failed} of {} files failed", files.len()));
+    }
+    Ok(())
+}
+
+/// Print the summary rows of all zip files, each prefixed with its path
+fn merge(files: &[String], options: &SummaryOptions, args: &Args) -> Result<(), String> {
+    let mut failed = 0;
+    for i in files {
+        match open(i, args) {
+            Ok(zip) => {
+                for row in zip.summary_rows(options) {
+                    print!("{i}:{row}{}", options.separator());
+                }
             }
             Err(e) => {
-                return Err(e);
+                print!("{i}: Error: {e}{}", options.separator());
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(format!("{failed} of {} files failed", files.len()));
+    }
+    Ok(())
+}
+
+/// Print the number of files (and optionally folders) in each zip file; errors get their own
+/// line instead of aborting the batch
+fn count(files: &[String], args: &Args) -> Result<(), String> {
+    let mut failed = 0;
+    for i in files {
+        match open(i, args) {
+            Ok(zip) => {
+                let stats = zip.stats();
+                if args.count_dirs {
+                    println!("{}", stats.files + stats.dirs);
+                } else {
+                    println!("{}", stats.files);
+                }
+            }
+            Err(e) => {
+                println!("{i}: Error: {e}");
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(format!("{failed} of {} files failed", files.len()));
+    }
+    Ok(())
+}
+
+/// Print the statistics of each zip file and/or the combined compression savings
+fn stats(files: &[String], args: &Args) -> Result<(), String> {
+    let (mut compressed, mut uncompressed) = (0, 0);
+    for i in files {
+        let zip = open(i, args)?;
+        let (c, u, percent) = zip.total_savings();
+        if args.stats {
+            println!("{i}: {}", zip.stats());
+            println!("{i}: compressed {c}, uncompressed {u}, {percent:.1}% saved");
+            println!("{i}: records: {}", zip.counts());
+            if zip.is_streamed() {
+                println!("{i}: streamed (random access may fail; scan linearly)");
+            } else {
+                println!("{i}: not streamed");
+            }
+        }
+        compressed += c;
+        uncompressed += u;
+    }
+    if args.totals {
+        println!(
+            "Total: compressed {compressed}, uncompressed {uncompressed}, {:.1}% saved",
+            zp_lib::savings_percent(compressed, uncompressed),
+        );
+    }
+    Ok(())
+}
+
+/// Print the audit findings for each zip file
+fn audit(files: &[String], args: &Args) -> Result<(), String> {
+    let mut count = 0;
+    for i in files {
+        let findings = open(i, args)?.audit();
+        if findings.is_empty() {
+            println!("{i}: OK");
+        }
+        for finding in &findings {
+            println!("{i}: {finding}");
+        }
+        count += findings.len();
+    }
+    if count > 0 {
+        return Err(format!("Audit found {count} issues"));
+    }
+    Ok(())
+}
+
+/// Print the check findings for each zip file
+fn check(files: &[String], args: &Args) -> Result<(), String> {
+    let mut count = 0;
+    for i in files {
+        // Parse leniently: a data descriptor flag mismatch makes the next record unreadable
+        let zip = Zip::reader()
+            .encoding(args.encoding)
+            .strict(args.strict)
+            .lenient(true)
+            .open(i)?;
+        escalate(&zip, i, args)?;
+        let findings = zip.check();
+        if findings.is_empty() && zip.truncated().is_none() {
+            println!("{i}: OK");
+        }
+        for finding in &findings {
+            println!("{i}: {finding}");
+        }
+        count += findings.len();
+        if let Some(e) = zip.truncated() {
+            println!("{i}: Stopped parsing: {e}");
+            count += 1;
+        }
+    }
+    if count > 0 {
+        return Err(format!("Check found {count} issues"));
+    }
+    Ok(())
+}
+
+/// Write the decompressed data of an item to stdout
+fn cat(file: &str, name: &str, args: &Args) -> Result<(), String> {
+    let zip = open(file, args)?;
+    let stdout = std::io::stdout();
+    let mut w = stdout.lock();
+    zip.extract_to(name, &mut w)
+}
+
+/// Print a hex dump of a byte range of a file, or of the local file record of an item
+///
+/// The byte range is read without parsing the file, so it also works on damaged zip files.
+fn dump(
+    file: &str,
+    offset: u64,
+    len: Option<u64>,
+    entry: Option<&str>,
+    args: &Args,
+) -> Result<(), String> {
+    let (offset, len) = match entry {
+        Some(name) => {
+            let zip = open(file, args)?;
+            let header = zip
+                .find(name)
+                .ok_or_else(|| format!("Item not found: `{name}`"))?;
+            let local = zip
+                .local_file_for(header)
+                .ok_or_else(|| format!("Local file header not found: `{name}`"))?;
+            (
+                zip.base_offset() + header.lfh_offset() as u64,
+                Some(local.record_size()),
+            )
+        }
+        None => (offset, len),
+    };
+    let io = |e: std::io::Error| e.to_string();
+    let mut f = std::fs::File::open(file).map_err(|e| format!("{e}: `{file}`"))?;
+    f.seek(SeekFrom::Start(offset)).map_err(io)?;
+    let mut bytes = vec![];
+    f.take(len.unwrap_or(u64::MAX))
+        .read_to_end(&mut bytes)
+        .map_err(io)?;
+    print!("{}", zp_lib::hexdump(&bytes, offset));
+    Ok(())
+}
+
+/// Print the local and central directory extra fields of an item
+fn extra(file: &str, name: &str, args: &Args) -> Result<(), String> {
+    let zip = open(file, args)?;
+    let header = zip
+        .find(name)
+        .ok_or_else(|| format!("Item not found: `{name}`"))?;
+    println!("--- Local file header");
+    match zip.local_file_for(header) {
+        Some(local) => print!("{}", zp_lib::extra_fields_verbose(local.extra_field())?),
+        None => println!("(missing)"),
+    }
+    println!("--- Central directory file header");
+    print!("{}", zp_lib::extra_fields_verbose(header.extra_field())?);
+    Ok(())
+}
+
+/// Extract the items of a zip file, or with `dry_run`, print the path each item would be
+/// extracted to, flagging collisions and unsafe paths
+fn extract(file: &str, dest: &str, dry_run: bool, args: &Args) -> Result<(), String> {
+    let zip = open(file, args)?;
+    let dest = std::path::Path::new(dest);
+    if !dry_run {
+        for (name, path) in zip.extract_all(dest)? {
+            println!("{name} -> {}", path.display());
+        }
+        return Ok(());
+    }
+    let mut seen = std::collections::HashSet::new();
+    for (name, path) in zip.extract_plan(dest) {
+        let collision = if seen.insert(path.clone()) {
+            ""
+        } else {
+            " (collision)"
+        };
+        println!("{name} -> {}{collision}", path.display());
+    }
+    let rejected = zip.unsafe_paths();
+    for (name, e) in &rejected {
+        println!("{name}: Error: {e}");
+    }
+    if !rejected.is_empty() {
+        return Err(format!("{} items have unsafe paths", rejected.len()));
+    }
+    Ok(())
+}
+
+/// Print the overview of a zip file
+fn info(file: &str, args: &Args) -> Result<(), String> {
+    println!("{}", open(file, args)?.info());
+    Ok(())
+}
+
+/// Print the file names in each zip file
+fn names(files: &[String], args: &Args) -> Result<(), String> {
+    for i in files {
+        for name in open(i, args)?.names() {
+            println!("{name}");
+        }
+    }
+    Ok(())
+}
+
+/// Print the `count` largest files in a zip file, largest first
+fn top(file: &str, count: usize, args: &Args) -> Result<(), String> {
+    let zip = open(file, args)?;
+    for i in zip.largest(count) {
+        let (compressed, uncompressed) = (i.compressed_size() as u64, i.uncompressed_size() as u64);
+        println!(
+            "{uncompressed}\t{compressed}\t{:.1}%\t{}",
+            zp_lib::savings_percent(compressed, uncompressed),
+            i.name(zip.encoding()),
+        );
+    }
+    Ok(())
+}
+
+/// Print the CRC-32 check of each item in each zip file
+fn verify(files: &[String], deep: bool, args: &Args) -> Result<(), String> {
+    let mut bad = 0;
+    for i in files {
+        for verification in open(i, args)?.verify(deep) {
+            println!("{i}: {verification}");
+            if verification.is_bad() {
+                bad += 1;
             }
         }
     }
+    if bad > 0 {
+        return Err(format!("{bad} items failed CRC-32 verification"));
+    }
+    Ok(())
+}
+
+/// Compare a zip file with a manifest and print each mismatch
+fn verify_manifest(file: &str, manifest: &str, args: &Args) -> Result<(), String> {
+    let expected = std::fs::read_to_string(manifest).map_err(|e| format!("{e}: `{manifest}`"))?;
+    let mismatches = open(file, args)?.matches_manifest(&parse_manifest(&expected)?);
+    for mismatch in &mismatches {
+        println!("{file}: {mismatch}");
+    }
+    if !mismatches.is_empty() {
+        return Err(format!(
+            "{} items do not match the manifest",
+            mismatches.len()
+        ));
+    }
     Ok(())
 }
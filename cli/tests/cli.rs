@@ -2,6 +2,7 @@ use assert_cmd::Command;
 
 const VERBOSE: &str = include_str!("../../exercise.zip-process-verbose.txt");
 const SUMMARY: &str = include_str!("../../exercise.zip-process-summary.txt");
+const PRETTY: &str = include_str!("../../exercise.zip-process-pretty.txt");
 
 // Helper functions
 
@@ -49,18 +50,64 @@ fn pass(bin: &str, args: &[&str], want: &str) {
         .stdout(format!("{}\n", want));
 }
 
+/// Path in the temporary folder that is unique to this test run and `name`, removed when dropped
+/// so that it is cleaned up even if the test fails
+struct Temp(std::path::PathBuf);
+
+impl Temp {
+    fn new(name: &str) -> Temp {
+        let path = std::env::temp_dir().join(format!("zp-cli-{}-{name}", std::process::id()));
+        Temp(path)
+    }
+
+    fn to_str(&self) -> &str {
+        self.0.to_str().unwrap()
+    }
+}
+
+impl Drop for Temp {
+    fn drop(&mut self) {
+        if self.0.is_dir() {
+            let _ = std::fs::remove_dir_all(&self.0);
+        } else {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+}
+
 // Tests
 
 #[test]
 fn version() {
-    for i in ["-V", "--version"].iter() {
-        pass("zp", &[i], &format!("zp {}", env!("CARGO_PKG_VERSION")));
-    }
+    let version = format!("zp {}", env!("CARGO_PKG_VERSION"));
+    pass("zp", &["-V"], &version);
+    pass(
+        "zp",
+        &["--version"],
+        &format!("{version}\nSupported compression methods: stored (0), deflated (8)"),
+    );
+}
+
+#[test]
+fn help_compression_methods() {
+    p("zp", &["-h"]);
+    let output = cmd("zp").arg("-h").output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .ends_with("\n\nSupported compression methods: stored (0), deflated (8)\n"));
 }
 
 #[test]
 fn verbose() {
     pass("zp", &["-v", "../exercise.zip"], VERBOSE);
+    pass("zp", &["--format", "verbose", "../exercise.zip"], VERBOSE);
+}
+
+#[test]
+fn no_eof_marker() {
+    let want = VERBOSE.strip_suffix("---\nEOF\n---\n").unwrap();
+    pass("zp", &["-v", "--no-eof-marker", "../exercise.zip"], want);
 }
 
 #[test]
@@ -68,6 +115,535 @@ fn summary() {
     pass("zp", &["../exercise.zip"], SUMMARY);
 }
 
+#[test]
+fn pretty() {
+    pass("zp", &["--format", "pretty", "../exercise.zip"], PRETTY);
+}
+
+#[test]
+fn encoding() {
+    pass("zp", &["--encoding", "cp437", "../exercise.zip"], SUMMARY);
+    pass(
+        "zp",
+        &["--encoding", "shift_jis", "../exercise.zip"],
+        SUMMARY,
+    );
+}
+
+#[test]
+fn show_encryption() {
+    let want = SUMMARY
+        .lines()
+        .map(|x| format!("{x}\tnone\n"))
+        .collect::<String>();
+    pass("zp", &["--show-encryption", "../exercise.zip"], &want);
+}
+
+#[test]
+fn show_method() {
+    pass(
+        "zp",
+        &[
+            "--show-method",
+            "--filter",
+            "folder00/*.txt",
+            "../exercise.zip",
+        ],
+        "\
+folder00/test00-00.txt\tfalse\t95\t2022-05-19T10:57:24\t\tdeflated
+folder00/test00-01.txt\tfalse\t0\t2021-08-25T13:04:38\tThis file doesn't have any content\tstored
+",
+    );
+}
+
+#[test]
+fn detailed() {
+    pass(
+        "zp",
+        &[
+            "--detailed",
+            "--show-method",
+            "--filter",
+            "folder00/*.txt",
+            "../exercise.zip",
+        ],
+        "\
+folder00/test00-00.txt\tfalse\t95\t2022-05-19T10:57:24\t\tdeflated\tdeflated\t91\t95
+folder00/test00-01.txt\tfalse\t0\t2021-08-25T13:04:38\tThis file doesn't have any content\tstored\tstored\t0\t0
+",
+    );
+}
+
+#[test]
+fn show_exec() {
+    // streamed.zip was made on Unix with mode 0o600, so nothing is executable
+    pass(
+        "zp",
+        &["--show-exec", "../streamed.zip"],
+        "\
+deflated.txt\tfalse\t184\t2022-05-19T10:51:38\t\t
+stored.txt\tfalse\t25\t2022-05-19T10:51:38\t\t
+empty.txt\tfalse\t0\t2022-05-19T10:51:38\t\t
+",
+    );
+}
+
+#[test]
+fn show_comment() {
+    // exercise.zip has no zip file comment
+    pass("zp", &["--show-comment", "../exercise.zip"], SUMMARY);
+}
+
+#[test]
+fn delimiter() {
+    let want = SUMMARY.replace('\t', "\0");
+    pass("zp", &["--delimiter", "\\0", "../exercise.zip"], &want);
+    let want = SUMMARY.replace('\t', "\0").replace('\n', "\0");
+    cmd("zp")
+        .args(&[
+            "--delimiter",
+            "\\0",
+            "--separator",
+            "\\0",
+            "../exercise.zip",
+        ])
+        .assert()
+        .success()
+        .stdout(want);
+    cmd("zp")
+        .args(&["-v", "--separator", "===\\n", "../exercise.zip"])
+        .assert()
+        .success()
+        .stdout(VERBOSE.replace("---\n", "===\n"));
+}
+
+#[test]
+fn summary_verify() {
+    let want = SUMMARY
+        .lines()
+        .map(|x| format!("{x}\tOK\n"))
+        .collect::<String>();
+    pass("zp", &["--verify", "--deep", "../exercise.zip"], &want);
+}
+
+#[test]
+fn tree_order() {
+    pass("zp", &["--tree-order", "../exercise.zip"], SUMMARY);
+}
+
+#[test]
+fn size_range() {
+    pass(
+        "zp",
+        &["--min-size", "1K", "--max-size", "1M", "../exercise.zip"],
+        "folder01/exercise.zip\tfalse\t2272\t2022-05-19T11:05:08\t\n",
+    );
+}
+
+#[test]
+fn tree() {
+    pass(
+        "zp",
+        &[
+            "--format",
+            "tree",
+            "--tree-style",
+            "ascii",
+            "--tree-sizes",
+            "--tree-dates",
+            "../exercise.zip",
+        ],
+        "\
+folder00/ (2022-05-19T10:51:38)
+|-- folder00-00/ (2022-05-19T10:51:18)
+|   |-- test00-00-00.txt (4, 2020-08-25T09:05:38)
+|   |-- test00-00-01.txt (125, 2022-05-19T10:56:30)
+|   `-- test00-00-02.txt (4, 2020-08-25T09:05:38)
+|-- test00-00.txt (95, 2022-05-19T10:57:24)
+`-- test00-01.txt (0, 2021-08-25T13:04:38)
+folder01/ (2022-05-19T10:51:26)
+|-- exercise.zip (2272, 2022-05-19T11:05:08)
+`-- test01-00.txt (127, 2022-05-19T10:53:46)
+test00.txt (4, 2020-08-25T09:05:38)
+test01.txt (4, 2020-08-25T09:05:38)
+test02.txt (4, 2020-08-25T09:05:38)
+",
+    );
+    pass(
+        "zp",
+        &[
+            "tree",
+            "--tree-style",
+            "ascii",
+            "--tree-folder-sizes",
+            "../exercise.zip",
+        ],
+        "\
+folder00/ (228)
+|-- folder00-00/ (133)
+|   |-- test00-00-00.txt
+|   |-- test00-00-01.txt
+|   `-- test00-00-02.txt
+|-- test00-00.txt
+`-- test00-01.txt
+folder01/ (2399)
+|-- exercise.zip
+`-- test01-00.txt
+test00.txt
+test01.txt
+test02.txt
+",
+    );
+}
+
+#[test]
+fn sbom() {
+    pass(
+        "zp",
+        &["--format", "sbom", "../exercise.zip"],
+        r#"{"format":"zp-sbom","version":1,"files":[{"name":"folder00/folder00-00/test00-00-00.txt","size":4,"crc32":3632233996,"sha256":"9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"},{"name":"folder00/folder00-00/test00-00-01.txt","size":125,"crc32":2734389538,"sha256":"fd91a31b8b88ad64d32648c82a65db4376ae644da07b393daa4eb9237d0af112"},{"name":"folder00/folder00-00/test00-00-02.txt","size":4,"crc32":3632233996,"sha256":"9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"},{"name":"folder00/test00-00.txt","size":95,"crc32":3134165730,"sha256":"6705c5708c1864b23d3f66372147d73ebf21cd8c4102a37309c46599bef9dd40"},{"name":"folder00/test00-01.txt","size":0,"crc32":0,"sha256":"e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"},{"name":"folder01/exercise.zip","size":2272,"crc32":3527235659,"sha256":"a27811204611c331420554fb0d3d3bb7f3cb0c4531c81d55990591912857068e"},{"name":"folder01/test01-00.txt","size":127,"crc32":4164089642,"sha256":"5d30bcedb15c20fe0c9a3a8b19e7f8fb59246b29e31db26682f46ccf55953ab3"},{"name":"test00.txt","size":4,"crc32":3632233996,"sha256":"9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"},{"name":"test01.txt","size":4,"crc32":3632233996,"sha256":"9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"},{"name":"test02.txt","size":4,"crc32":3632233996,"sha256":"9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"}]}"#,
+    );
+    let output = cmd("zp")
+        .args(&["--format", "sbom", "--json-pretty", "../exercise.zip"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with(
+        "\
+{
+  \"format\": \"zp-sbom\",
+  \"version\": 1,
+  \"files\": [
+    {
+      \"name\": \"folder00/folder00-00/test00-00-00.txt\",
+      \"size\": 4,
+      \"crc32\": 3632233996,
+      \"sha256\": \"9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08\"
+    },
+"
+    ));
+    assert!(stdout.ends_with("    }\n  ]\n}\n"));
+}
+
+#[test]
+fn json() {
+    pass(
+        "zp",
+        &["--format", "json", "../exercise.zip"],
+        r#"[{"name":"folder00/","is_dir":true,"offset":0,"compressed_size":0,"uncompressed_size":0,"crc32":0,"method":0,"modified":"2022-05-19T10:51:38","comment":""},{"name":"folder00/folder00-00/","is_dir":true,"offset":39,"compressed_size":0,"uncompressed_size":0,"crc32":0,"method":0,"modified":"2022-05-19T10:51:18","comment":"A nested folder"},{"name":"folder00/folder00-00/test00-00-00.txt","is_dir":false,"offset":90,"compressed_size":4,"uncompressed_size":4,"crc32":3632233996,"method":0,"modified":"2020-08-25T09:05:38","comment":""},{"name":"folder00/folder00-00/test00-00-01.txt","is_dir":false,"offset":161,"compressed_size":113,"uncompressed_size":125,"crc32":2734389538,"method":8,"modified":"2022-05-19T10:56:30","comment":""},{"name":"folder00/folder00-00/test00-00-02.txt","is_dir":false,"offset":341,"compressed_size":4,"uncompressed_size":4,"crc32":3632233996,"method":0,"modified":"2020-08-25T09:05:38","comment":""},{"name":"folder00/test00-00.txt","is_dir":false,"offset":412,"compressed_size":91,"uncompressed_size":95,"crc32":3134165730,"method":8,"modified":"2022-05-19T10:57:24","comment":""},{"name":"folder00/test00-01.txt","is_dir":false,"offset":555,"compressed_size":0,"uncompressed_size":0,"crc32":0,"method":0,"modified":"2021-08-25T13:04:38","comment":"This file doesn't have any content"},{"name":"folder01/","is_dir":true,"offset":607,"compressed_size":0,"uncompressed_size":0,"crc32":0,"method":0,"modified":"2022-05-19T10:51:26","comment":""},{"name":"folder01/exercise.zip","is_dir":false,"offset":646,"compressed_size":961,"uncompressed_size":2272,"crc32":3527235659,"method":8,"modified":"2022-05-19T11:05:08","comment":""},{"name":"folder01/test01-00.txt","is_dir":false,"offset":1658,"compressed_size":108,"uncompressed_size":127,"crc32":4164089642,"method":8,"modified":"2022-05-19T10:53:46","comment":"This is a comment"},{"name":"test00.txt","is_dir":false,"offset":1818,"compressed_size":4,"uncompressed_size":4,"crc32":3632233996,"method":0,"modified":"2020-08-25T09:05:38","comment":"A top level file"},{"name":"test01.txt","is_dir":false,"offset":1862,"compressed_size":4,"uncompressed_size":4,"crc32":3632233996,"method":0,"modified":"2020-08-25T09:05:38","comment":""},{"name":"test02.txt","is_dir":false,"offset":1906,"compressed_size":4,"uncompressed_size":4,"crc32":3632233996,"method":0,"modified":"2020-08-25T09:05:38","comment":""}]"#,
+    );
+    pass(
+        "zp",
+        &["--format", "json", "--json-pretty", "../exercise.zip"],
+        r#"[
+  {
+    "name": "folder00/",
+    "is_dir": true,
+    "offset": 0,
+    "compressed_size": 0,
+    "uncompressed_size": 0,
+    "crc32": 0,
+    "method": 0,
+    "modified": "2022-05-19T10:51:38",
+    "comment": ""
+  },
+  {
+    "name": "folder00/folder00-00/",
+    "is_dir": true,
+    "offset": 39,
+    "compressed_size": 0,
+    "uncompressed_size": 0,
+    "crc32": 0,
+    "method": 0,
+    "modified": "2022-05-19T10:51:18",
+    "comment": "A nested folder"
+  },
+  {
+    "name": "folder00/folder00-00/test00-00-00.txt",
+    "is_dir": false,
+    "offset": 90,
+    "compressed_size": 4,
+    "uncompressed_size": 4,
+    "crc32": 3632233996,
+    "method": 0,
+    "modified": "2020-08-25T09:05:38",
+    "comment": ""
+  },
+  {
+    "name": "folder00/folder00-00/test00-00-01.txt",
+    "is_dir": false,
+    "offset": 161,
+    "compressed_size": 113,
+    "uncompressed_size": 125,
+    "crc32": 2734389538,
+    "method": 8,
+    "modified": "2022-05-19T10:56:30",
+    "comment": ""
+  },
+  {
+    "name": "folder00/folder00-00/test00-00-02.txt",
+    "is_dir": false,
+    "offset": 341,
+    "compressed_size": 4,
+    "uncompressed_size": 4,
+    "crc32": 3632233996,
+    "method": 0,
+    "modified": "2020-08-25T09:05:38",
+    "comment": ""
+  },
+  {
+    "name": "folder00/test00-00.txt",
+    "is_dir": false,
+    "offset": 412,
+    "compressed_size": 91,
+    "uncompressed_size": 95,
+    "crc32": 3134165730,
+    "method": 8,
+    "modified": "2022-05-19T10:57:24",
+    "comment": ""
+  },
+  {
+    "name": "folder00/test00-01.txt",
+    "is_dir": false,
+    "offset": 555,
+    "compressed_size": 0,
+    "uncompressed_size": 0,
+    "crc32": 0,
+    "method": 0,
+    "modified": "2021-08-25T13:04:38",
+    "comment": "This file doesn't have any content"
+  },
+  {
+    "name": "folder01/",
+    "is_dir": true,
+    "offset": 607,
+    "compressed_size": 0,
+    "uncompressed_size": 0,
+    "crc32": 0,
+    "method": 0,
+    "modified": "2022-05-19T10:51:26",
+    "comment": ""
+  },
+  {
+    "name": "folder01/exercise.zip",
+    "is_dir": false,
+    "offset": 646,
+    "compressed_size": 961,
+    "uncompressed_size": 2272,
+    "crc32": 3527235659,
+    "method": 8,
+    "modified": "2022-05-19T11:05:08",
+    "comment": ""
+  },
+  {
+    "name": "folder01/test01-00.txt",
+    "is_dir": false,
+    "offset": 1658,
+    "compressed_size": 108,
+    "uncompressed_size": 127,
+    "crc32": 4164089642,
+    "method": 8,
+    "modified": "2022-05-19T10:53:46",
+    "comment": "This is a comment"
+  },
+  {
+    "name": "test00.txt",
+    "is_dir": false,
+    "offset": 1818,
+    "compressed_size": 4,
+    "uncompressed_size": 4,
+    "crc32": 3632233996,
+    "method": 0,
+    "modified": "2020-08-25T09:05:38",
+    "comment": "A top level file"
+  },
+  {
+    "name": "test01.txt",
+    "is_dir": false,
+    "offset": 1862,
+    "compressed_size": 4,
+    "uncompressed_size": 4,
+    "crc32": 3632233996,
+    "method": 0,
+    "modified": "2020-08-25T09:05:38",
+    "comment": ""
+  },
+  {
+    "name": "test02.txt",
+    "is_dir": false,
+    "offset": 1906,
+    "compressed_size": 4,
+    "uncompressed_size": 4,
+    "crc32": 3632233996,
+    "method": 0,
+    "modified": "2020-08-25T09:05:38",
+    "comment": ""
+  }
+]"#,
+    );
+}
+
+#[test]
+fn crc_size() {
+    pass(
+        "zp",
+        &["--crc", "0xA2FB7922", "../exercise.zip"],
+        "folder00/folder00-00/test00-00-01.txt\tfalse\t125\t2022-05-19T10:56:30\t\n",
+    );
+    pass(
+        "zp",
+        &["--size", "95", "../exercise.zip"],
+        "folder00/test00-00.txt\tfalse\t95\t2022-05-19T10:57:24\t\n",
+    );
+}
+
+#[test]
+fn utc() {
+    pass(
+        "zp",
+        &["--size", "95", "--assume-utc", "../exercise.zip"],
+        "folder00/test00-00.txt\tfalse\t95\t2022-05-19T10:57:24Z\t\n",
+    );
+    pass(
+        "zp",
+        &["--size", "95", "--local-tz=-05:00", "../exercise.zip"],
+        "folder00/test00-00.txt\tfalse\t95\t2022-05-19T15:57:24Z\t\n",
+    );
+    pass(
+        "zp",
+        &["--size", "95", "--local-tz", "+0200", "../exercise.zip"],
+        "folder00/test00-00.txt\tfalse\t95\t2022-05-19T08:57:24Z\t\n",
+    );
+}
+
+#[test]
+fn filter_exclude() {
+    pass(
+        "zp",
+        &[
+            "--filter",
+            "**/*.txt",
+            "--exclude",
+            "folder00/**",
+            "../exercise.zip",
+        ],
+        "\
+folder01/test01-00.txt\tfalse\t127\t2022-05-19T10:53:46\tThis is a comment
+test00.txt\tfalse\t4\t2020-08-25T09:05:38\tA top level file
+test01.txt\tfalse\t4\t2020-08-25T09:05:38\t
+test02.txt\tfalse\t4\t2020-08-25T09:05:38\t",
+    );
+    pass(
+        "zp",
+        &[
+            "--exclude",
+            "folder0?/**",
+            "--exclude",
+            "*0.txt",
+            "../exercise.zip",
+        ],
+        "\
+test01.txt\tfalse\t4\t2020-08-25T09:05:38\t
+test02.txt\tfalse\t4\t2020-08-25T09:05:38\t",
+    );
+}
+
+#[test]
+fn multi() {
+    let path = Temp::new("multi.zip");
+    let bytes = std::fs::read("../exercise.zip").unwrap();
+    std::fs::write(&path.0, [bytes.clone(), bytes].concat()).unwrap();
+    pass(
+        "zp",
+        &["--multi", path.to_str()],
+        &format!("{SUMMARY}\n--- zip 2 at offset 3387\n{SUMMARY}"),
+    );
+    pass("zp", &["--multi", "../exercise.zip"], SUMMARY);
+}
+
+#[test]
+fn verify_manifest() {
+    let temp = Temp::new("verify-manifest.txt");
+    let manifest = cmd("zp")
+        .args(["--format", "manifest", "../exercise.zip"])
+        .output()
+        .unwrap()
+        .stdout;
+    std::fs::write(&temp.0, &manifest).unwrap();
+    let path = temp.to_str();
+    cmd("zp")
+        .args(["verify-manifest", "../exercise.zip", path])
+        .assert()
+        .success()
+        .stdout("");
+
+    let manifest = String::from_utf8(manifest)
+        .unwrap()
+        .replace("test02.txt\t1906\t4\t4\t", "test02.txt\t1906\t4\t5\t")
+        .replace("test00.txt", "renamed.txt");
+    std::fs::write(path, manifest).unwrap();
+    let args = ["verify-manifest", "../exercise.zip", path];
+    p("zp", &args);
+    cmd("zp")
+        .args(args)
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(
+            "\
+../exercise.zip: Missing `renamed.txt`
+../exercise.zip: Differs `test02.txt`: CRC-32 0xd87f7e0c and size 4, expected 0xd87f7e0c and 5
+../exercise.zip: Extra `test00.txt`
+",
+        )
+        .stderr("Error: \"3 items do not match the manifest\"\n");
+}
+
+#[test]
+fn ranges() {
+    pass(
+        "zp",
+        &["--format", "ranges", "../exercise.zip"],
+        "\
+folder00/folder00-00/test00-00-00.txt	157	4
+folder00/folder00-00/test00-00-01.txt	228	113
+folder00/folder00-00/test00-00-02.txt	408	4
+folder00/test00-00.txt	464	91
+folder00/test00-01.txt	607	0
+folder01/exercise.zip	697	961
+folder01/test01-00.txt	1710	108
+test00.txt	1858	4
+test01.txt	1902	4
+test02.txt	1946	4
+",
+    );
+}
+
+#[test]
+fn manifest() {
+    pass(
+        "zp",
+        &["--format", "manifest", "../exercise.zip"],
+        "\
+name	offset	compressed_size	uncompressed_size	crc32	method
+folder00/	0	0	0	0	0
+folder00/folder00-00/	39	0	0	0	0
+folder00/folder00-00/test00-00-00.txt	90	4	4	3632233996	0
+folder00/folder00-00/test00-00-01.txt	161	113	125	2734389538	8
+folder00/folder00-00/test00-00-02.txt	341	4	4	3632233996	0
+folder00/test00-00.txt	412	91	95	3134165730	8
+folder00/test00-01.txt	555	0	0	0	0
+folder01/	607	0	0	0	0
+folder01/exercise.zip	646	961	2272	3527235659	8
+folder01/test01-00.txt	1658	108	127	4164089642	8
+test00.txt	1818	4	4	3632233996	0
+test01.txt	1862	4	4	3632233996	0
+test02.txt	1906	4	4	3632233996	0
+",
+    );
+}
+
 #[test]
 fn not_a_file() {
     fail("zp", &["."], 1, "Path is not a file: `.`");
@@ -102,3 +678,568 @@ fn eof() {
         "Path does not exist: `nonexistent.zip`",
     );
 }
+
+#[test]
+fn oneline() {
+    pass(
+        "zp",
+        &["--oneline", "../exercise.zip", "../exercise.zip"],
+        "\
+../exercise.zip: 10 files, 3 dirs, total 2639, not encrypted
+../exercise.zip: 10 files, 3 dirs, total 2639, not encrypted\
+        ",
+    );
+}
+
+#[test]
+fn oneline_errors() {
+    let args = ["--oneline", "nonexistent.zip", "../exercise.zip"];
+    p("zp", &args);
+    cmd("zp")
+        .args(args)
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(
+            "\
+nonexistent.zip: Error: Path does not exist: `nonexistent.zip`
+../exercise.zip: 10 files, 3 dirs, total 2639, not encrypted
+",
+        )
+        .stderr("Error: \"1 of 2 files failed\"\n");
+}
+
+#[test]
+fn merge() {
+    let args = [
+        "--merge",
+        "--size",
+        "4",
+        "--filter",
+        "test0?.txt",
+        "../exercise.zip",
+        "nonexistent.zip",
+        "../exercise.zip",
+    ];
+    p("zp", &args);
+    cmd("zp")
+        .args(args)
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(
+            "\
+../exercise.zip:test00.txt\tfalse\t4\t2020-08-25T09:05:38\tA top level file
+../exercise.zip:test01.txt\tfalse\t4\t2020-08-25T09:05:38\t
+../exercise.zip:test02.txt\tfalse\t4\t2020-08-25T09:05:38\t
+nonexistent.zip: Error: Path does not exist: `nonexistent.zip`
+../exercise.zip:test00.txt\tfalse\t4\t2020-08-25T09:05:38\tA top level file
+../exercise.zip:test01.txt\tfalse\t4\t2020-08-25T09:05:38\t
+../exercise.zip:test02.txt\tfalse\t4\t2020-08-25T09:05:38\t
+",
+        )
+        .stderr("Error: \"1 of 3 files failed\"\n");
+}
+
+#[test]
+fn timing() {
+    let output = cmd("zp")
+        .args(&["--timing", "../exercise.zip"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("{SUMMARY}\n")
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    // Every record is read, so at least the 3387 bytes of the file
+    let bytes = stderr
+        .strip_prefix("../exercise.zip: read ")
+        .and_then(|x| x.split_once(" bytes, parsed in "))
+        .map(|(bytes, _)| bytes.parse::<u64>().unwrap())
+        .unwrap();
+    assert!(bytes >= 3387);
+    assert!(stderr.contains(", formatted in "));
+}
+
+#[test]
+fn hash() {
+    let output = cmd("zp")
+        .args(&["--hash", "../exercise.zip"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("{SUMMARY}\n")
+    );
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        include_str!("../../exercise.zip.sha256").replace("exercise.zip", "../exercise.zip"),
+    );
+}
+
+#[test]
+fn hash_multi() {
+    // The concatenated zip files share the bytes of one file, so there is no digest per zip file
+    p("zp", &["--hash", "--multi", "../exercise.zip"]);
+    cmd("zp")
+        .args(&["--hash", "--multi", "../exercise.zip"])
+        .assert()
+        .failure()
+        .code(2);
+}
+
+#[test]
+fn count() {
+    pass("zp", &["--count", "../exercise.zip"], "10");
+    pass("zp", &["--count", "--count-dirs", "../exercise.zip"], "13");
+    pass(
+        "zp",
+        &["--count", "../exercise.zip", "../exercise.zip"],
+        "10\n10",
+    );
+}
+
+#[test]
+fn count_errors() {
+    let args = ["--count", "nonexistent.zip", "../exercise.zip"];
+    p("zp", &args);
+    cmd("zp")
+        .args(args)
+        .assert()
+        .failure()
+        .code(1)
+        .stdout("nonexistent.zip: Error: Path does not exist: `nonexistent.zip`\n10\n")
+        .stderr("Error: \"1 of 2 files failed\"\n");
+}
+
+#[test]
+fn conflicting_modes() {
+    // Each of these prints its own output, so they cannot be combined
+    for args in [
+        &["--oneline", "--count"][..],
+        &["--count", "--format", "json"],
+        &["--audit", "-v"],
+        &["--check", "--stats"],
+        &["--stats", "--merge"],
+        &["--totals", "--format", "tree"],
+        &["--merge", "-v"],
+    ] {
+        let args = [args, &["../exercise.zip"]].concat();
+        p("zp", &args);
+        cmd("zp").args(&args).assert().failure().code(2);
+    }
+}
+
+#[test]
+fn stats() {
+    pass(
+        "zp",
+        &["--stats", "--totals", "../exercise.zip", "../exercise.zip"],
+        "\
+../exercise.zip: 10 files, 3 dirs, total 2639, not encrypted
+../exercise.zip: compressed 1293, uncompressed 2639, 51.0% saved
+../exercise.zip: records: local 13, central 13, eocd 1, consistent
+../exercise.zip: not streamed
+../exercise.zip: 10 files, 3 dirs, total 2639, not encrypted
+../exercise.zip: compressed 1293, uncompressed 2639, 51.0% saved
+../exercise.zip: records: local 13, central 13, eocd 1, consistent
+../exercise.zip: not streamed
+Total: compressed 2586, uncompressed 5278, 51.0% saved\
+",
+    );
+    pass(
+        "zp",
+        &["--totals", "../exercise.zip"],
+        "Total: compressed 1293, uncompressed 2639, 51.0% saved",
+    );
+}
+
+#[test]
+fn follow() {
+    let nested = SUMMARY
+        .lines()
+        .filter(|x| !x.starts_with("folder01/exercise.zip\t"))
+        .map(|x| format!("{x}\n"))
+        .collect::<String>();
+    pass(
+        "zp",
+        &["--follow", "folder01/exercise.zip", "../exercise.zip"],
+        &format!("{SUMMARY}\n--- folder01/exercise.zip\n{nested}"),
+    );
+    fail(
+        "zp",
+        &["--follow", "nonexistent", "../exercise.zip"],
+        1,
+        "Item not found: `nonexistent`",
+    );
+}
+
+#[test]
+fn max_depth() {
+    pass(
+        "zp",
+        &["--format", "tree", "--max-depth", "1", "../exercise.zip"],
+        "\
+folder00/
+└── ... (6 items below the maximum depth of 1)
+folder01/
+└── ... (2 items below the maximum depth of 1)
+test00.txt
+test01.txt
+test02.txt
+",
+    );
+    fail(
+        "zp",
+        &[
+            "--max-depth",
+            "0",
+            "--follow",
+            "folder01/exercise.zip",
+            "../exercise.zip",
+        ],
+        1,
+        "Cannot follow more than 0 nested zip files (see `--max-depth`)",
+    );
+}
+
+#[test]
+fn audit() {
+    pass("zp", &["--audit", "../exercise.zip"], "../exercise.zip: OK");
+}
+
+#[test]
+fn check() {
+    pass("zp", &["--check", "../exercise.zip"], "../exercise.zip: OK");
+}
+
+#[test]
+fn check_data_descriptor() {
+    // A stored local file `a.txt` that does not set the data descriptor flag, followed by a data
+    // descriptor
+    let mut bytes = b"PK\x03\x04\x14\0\0\0\0\0\0\0\0\0".to_vec();
+    bytes.extend([0x0c, 0x7e, 0x7f, 0xd8, 4, 0, 0, 0, 4, 0, 0, 0, 5, 0, 0, 0]);
+    bytes.extend(b"a.txttestPK\x07\x08");
+    bytes.extend([0x0c, 0x7e, 0x7f, 0xd8, 4, 0, 0, 0, 4, 0, 0, 0]);
+    let path = Temp::new("check-data-descriptor.zip");
+    std::fs::write(&path.0, bytes).unwrap();
+    let finding = "Local file `a.txt` at offset 0 has a data descriptor but does not set the data \
+        descriptor flag";
+    let args = ["--check", path.to_str()];
+    p("zp", &args);
+    cmd("zp")
+        .args(args)
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(format!(
+            "{path}: {finding}\n{path}: Stopped parsing: Invalid signature: `504b0708` \
+            ({finding})\n",
+            path = path.to_str(),
+        ))
+        .stderr("Error: \"Check found 2 issues\"\n");
+}
+
+#[test]
+fn strict() {
+    pass("zp", &["--strict", "../exercise.zip"], SUMMARY);
+    pass("zp", &["--strict", "-v", "../exercise.zip"], VERBOSE);
+}
+
+#[test]
+fn strict_encoding() {
+    // The name `\x81.txt` is not valid UTF-8: a guess with the default encoding, lossy as UTF-8,
+    // and `\u{fc}.txt` as CP437
+    fail(
+        "zp",
+        &["--strict", "../warnings.zip"],
+        1,
+        "File name at offset 39 is not valid UTF-8: `812e747874`",
+    );
+    fail(
+        "zp",
+        &["--strict", "--encoding", "utf-8", "../warnings.zip"],
+        1,
+        "File name at offset 39 cannot be decoded without loss: `812e747874`",
+    );
+    let args = ["--strict", "--encoding", "cp437", "../warnings.zip"];
+    p("zp", &args);
+    let output = cmd("zp").args(args).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("\n\u{fc}.txt\tfalse\t6\t"));
+}
+
+#[test]
+fn strict_warnings() {
+    pass("zp", &["--strict-warnings", "../exercise.zip"], SUMMARY);
+    let args = [
+        "--strict-warnings",
+        "--encoding",
+        "utf-8",
+        "../warnings.zip",
+    ];
+    p("zp", &args);
+    cmd("zp")
+        .args(args)
+        .assert()
+        .failure()
+        .code(1)
+        .stdout("")
+        .stderr(
+            "\
+../warnings.zip: Warning: File name `812e747874` (hex) at offset 236 cannot be decoded without loss
+../warnings.zip: Warning: File name `a.txt` appears 2 times
+../warnings.zip: Warning: Item `zeros.bin` expands from 27 to 10000 bytes, more than 100 times
+Error: \"Found 3 warnings in `../warnings.zip`\"
+",
+        );
+
+    // Warnings are not errors by default
+    cmd("zp").arg("../warnings.zip").assert().success();
+}
+
+#[test]
+fn extra() {
+    pass(
+        "zp",
+        &["extra", "../exercise.zip", "folder00/"],
+        "\
+--- Local file header
+(none)
+--- Central directory file header
+tag = 0x000a (NTFS)
+size = 0x0020 (32)
+data = \"00000000010018005bef1d52986bd8015bef1d52986bd801c87ec7b5e87ad601\"
+mtime = 2022-05-19T15:51:36.4324187Z
+atime = 2022-05-19T15:51:36.4324187Z
+ctime = 2020-08-25T14:04:55.4759880Z\
+        ",
+    );
+}
+
+#[test]
+fn extra_not_found() {
+    fail(
+        "zp",
+        &["extra", "../exercise.zip", "nonexistent"],
+        1,
+        "Item not found: `nonexistent`",
+    );
+}
+
+#[test]
+fn extra_file_name() {
+    // A zip file named like a subcommand is reached with a path or after `--`
+    let dir = Temp::new("extra-file-name");
+    std::fs::create_dir_all(&dir.0).unwrap();
+    std::fs::copy("../exercise.zip", dir.0.join("extra")).unwrap();
+    for args in [&["./extra"][..], &["--", "extra"]] {
+        p("zp", args);
+        cmd("zp")
+            .current_dir(&dir.0)
+            .args(args)
+            .assert()
+            .success()
+            .stdout(format!("{SUMMARY}\n"));
+    }
+    // Without the arguments of the subcommand, the existing file is listed
+    p("zp", &["extra"]);
+    cmd("zp")
+        .current_dir(&dir.0)
+        .arg("extra")
+        .assert()
+        .success()
+        .stdout(format!("{SUMMARY}\n"));
+
+    // With them, the subcommand wins
+    std::fs::copy("../exercise.zip", dir.0.join("a.zip")).unwrap();
+    let args = ["extra", "a.zip", "test00.txt"];
+    p("zp", &args);
+    let output = cmd("zp").current_dir(&dir.0).args(args).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("--- Local file header\n"));
+}
+
+#[test]
+fn cat() {
+    cmd("zp")
+        .args(&["cat", "../exercise.zip", "test00.txt"])
+        .assert()
+        .success()
+        .stdout("test");
+    cmd("zp")
+        .args(&["cat", "../exercise.zip", "folder00/test00-00.txt"])
+        .assert()
+        .success()
+        .stdout(
+            "\u{201c}There\u{2019}s no point in being grown up if you can\u{2019}t be childish \
+            sometimes.\u{201d} - 4th Doctor\r\n",
+        );
+    fail(
+        "zp",
+        &["cat", "../exercise.zip", "folder00/"],
+        1,
+        "Item is a folder: `folder00/`",
+    );
+    fail(
+        "zp",
+        &["cat", "../exercise.zip", "nonexistent"],
+        1,
+        "Item not found: `nonexistent`",
+    );
+}
+
+#[test]
+fn extract_dry_run() {
+    pass(
+        "zp",
+        &["extract", "--dry-run", "-d", "out", "../exercise.zip"],
+        "\
+folder00/ -> out/folder00
+folder00/folder00-00/ -> out/folder00/folder00-00
+folder00/folder00-00/test00-00-00.txt -> out/folder00/folder00-00/test00-00-00.txt
+folder00/folder00-00/test00-00-01.txt -> out/folder00/folder00-00/test00-00-01.txt
+folder00/folder00-00/test00-00-02.txt -> out/folder00/folder00-00/test00-00-02.txt
+folder00/test00-00.txt -> out/folder00/test00-00.txt
+folder00/test00-01.txt -> out/folder00/test00-01.txt
+folder01/ -> out/folder01
+folder01/exercise.zip -> out/folder01/exercise.zip
+folder01/test01-00.txt -> out/folder01/test01-00.txt
+test00.txt -> out/test00.txt
+test01.txt -> out/test01.txt
+test02.txt -> out/test02.txt\
+",
+    );
+}
+
+#[test]
+fn extract() {
+    let dest = Temp::new("extract");
+    let want = format!("test00.txt -> {}\n", dest.0.join("test00.txt").display());
+    p("zp", &["extract", "-d", dest.to_str(), "../exercise.zip"]);
+    let output = cmd("zp")
+        .args(["extract", "-d", dest.to_str(), "../exercise.zip"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout).unwrap().contains(&want));
+    assert_eq!(std::fs::read(dest.0.join("test00.txt")).unwrap(), b"test");
+    let test01_00 = std::fs::read(dest.0.join("folder01/test01-00.txt")).unwrap();
+    assert_eq!(test01_00.len(), 127);
+    assert!(dest.0.join("folder00/folder00-00").is_dir());
+}
+
+#[test]
+fn top() {
+    pass(
+        "zp",
+        &["top", "../exercise.zip", "3"],
+        "\
+2272\t961\t57.7%\tfolder01/exercise.zip
+127\t108\t15.0%\tfolder01/test01-00.txt
+125\t113\t9.6%\tfolder00/folder00-00/test00-00-01.txt",
+    );
+
+    // Defaults to 10 files
+    let output = cmd("zp")
+        .args(&["top", "../exercise.zip"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap().lines().count(),
+        10
+    );
+}
+
+#[test]
+fn dump() {
+    pass(
+        "zp",
+        &["dump", "--offset", "32", "--len", "7", "../exercise.zip"],
+        "00000020  6c 64 65 72 30 30 2f                              |lder00/|",
+    );
+    pass(
+        "zp",
+        &["dump", "--entry", "test00.txt", "../exercise.zip"],
+        "\
+0000071a  50 4b 03 04 0a 00 00 00  00 00 b3 48 19 51 0c 7e  |PK.........H.Q.~|
+0000072a  7f d8 04 00 00 00 04 00  00 00 0a 00 00 00 74 65  |..............te|
+0000073a  73 74 30 30 2e 74 78 74  74 65 73 74              |st00.txttest|",
+    );
+    fail(
+        "zp",
+        &["dump", "--entry", "missing.txt", "../exercise.zip"],
+        1,
+        "Item not found: `missing.txt`",
+    );
+}
+
+#[test]
+fn info() {
+    pass(
+        "zp",
+        &["info", "../exercise.zip"],
+        "\
+format: zip
+entries: 13 (10 files, 3 dirs)
+total size: 2639 (compressed 1293, 51.0% saved)
+compression methods: 0 (stored), 8 (deflated)
+encrypted: no
+required version: 2.0
+multi-disk: no
+streamed: no
+comment: (none)",
+    );
+}
+
+#[test]
+fn names() {
+    pass(
+        "zp",
+        &["names", "../exercise.zip"],
+        "\
+folder00/
+folder00/folder00-00/
+folder00/folder00-00/test00-00-00.txt
+folder00/folder00-00/test00-00-01.txt
+folder00/folder00-00/test00-00-02.txt
+folder00/test00-00.txt
+folder00/test00-01.txt
+folder01/
+folder01/exercise.zip
+folder01/test01-00.txt
+test00.txt
+test01.txt
+test02.txt\
+",
+    );
+}
+
+#[test]
+fn verify() {
+    pass(
+        "zp",
+        &["verify", "--deep", "../exercise.zip"],
+        "\
+../exercise.zip: folder00/: OK
+../exercise.zip: folder00/folder00-00/: OK
+../exercise.zip: folder00/folder00-00/test00-00-00.txt: OK
+../exercise.zip: folder00/folder00-00/test00-00-01.txt: OK
+../exercise.zip: folder00/folder00-00/test00-00-02.txt: OK
+../exercise.zip: folder00/test00-00.txt: OK
+../exercise.zip: folder00/test00-01.txt: OK
+../exercise.zip: folder01/: OK
+../exercise.zip: folder01/exercise.zip: OK
+../exercise.zip: folder01/test01-00.txt: OK
+../exercise.zip: test00.txt: OK
+../exercise.zip: test01.txt: OK
+../exercise.zip: test02.txt: OK\
+",
+    );
+}
@@ -0,0 +1,22 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zp_lib::Zip;
+
+// Any input must produce `Ok` or `Err`, never a panic; formatting a parsed zip file must not
+// panic either
+fuzz_target!(|data: &[u8]| {
+    if let Ok(zip) = Zip::from_bytes(data) {
+        let _ = zip.summary();
+        let _ = zip.verbose();
+        let _ = zip.pretty();
+        let _ = zip.json(true);
+        let _ = zip.manifest();
+        let _ = zip.ranges();
+        let _ = zip.tree();
+        let _ = zip.info();
+        let _ = zip.check();
+        let _ = zip.audit();
+        let _ = zip.nonconformances();
+    }
+});
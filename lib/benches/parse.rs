@@ -0,0 +1,76 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use std::io::{BufReader, Cursor};
+use zp_lib::Zip;
+
+/// Stored zip file with `count` items of `size` bytes each
+fn synthetic(count: usize, size: usize) -> Vec<u8> {
+    let data = vec![b'x'; size];
+    let mut v = vec![];
+    let mut cd = vec![];
+    for i in 0..count {
+        let name = format!("folder{:02}/file{i:06}.txt", i % 100);
+        let offset = v.len() as u32;
+        v.extend(b"PK\x03\x04");
+        for n in [20, 0, 0, 0, 0x21] {
+            v.extend(u16::to_le_bytes(n));
+        }
+        for n in [0, size as u32, size as u32] {
+            v.extend(u32::to_le_bytes(n));
+        }
+        for n in [name.len() as u16, 0] {
+            v.extend(u16::to_le_bytes(n));
+        }
+        v.extend(name.as_bytes());
+        v.extend(&data);
+        cd.extend(b"PK\x01\x02");
+        for n in [20, 20, 0, 0, 0, 0x21] {
+            cd.extend(u16::to_le_bytes(n));
+        }
+        for n in [0, size as u32, size as u32] {
+            cd.extend(u32::to_le_bytes(n));
+        }
+        for n in [name.len() as u16, 0, 0, 0, 0] {
+            cd.extend(u16::to_le_bytes(n));
+        }
+        for n in [0, offset] {
+            cd.extend(u32::to_le_bytes(n));
+        }
+        cd.extend(name.as_bytes());
+    }
+    let cd_offset = v.len() as u32;
+    let cd_size = cd.len() as u32;
+    v.extend(cd);
+    v.extend(b"PK\x05\x06");
+    for n in [0, 0, count as u16, count as u16] {
+        v.extend(u16::to_le_bytes(n));
+    }
+    for n in [cd_size, cd_offset] {
+        v.extend(u32::to_le_bytes(n));
+    }
+    v.extend(u16::to_le_bytes(0));
+    v
+}
+
+/// Benchmark processing, summarizing, and verbosely analyzing the zip file `bytes`
+fn bench(c: &mut Criterion, name: &str, bytes: &[u8]) {
+    let process = || Zip::process(&mut BufReader::new(Cursor::new(bytes))).unwrap();
+    let zip = process();
+    let mut group = c.benchmark_group(name);
+    group.throughput(Throughput::Bytes(bytes.len() as u64));
+    group.bench_function("process", |b| b.iter(|| black_box(process())));
+    group.bench_function("summary", |b| b.iter(|| black_box(zip.summary().unwrap())));
+    group.bench_function("verbose", |b| b.iter(|| black_box(zip.verbose().unwrap())));
+    group.finish();
+}
+
+fn exercise(c: &mut Criterion) {
+    let bytes = std::fs::read("../exercise.zip").unwrap();
+    bench(c, "exercise.zip", &bytes);
+}
+
+fn large(c: &mut Criterion) {
+    bench(c, "synthetic (10000 items)", &synthetic(10000, 64));
+}
+
+criterion_group!(benches, exercise, large);
+criterion_main!(benches);
@@ -0,0 +1,96 @@
+use crate::*;
+
+/// Discrepancy between a central directory file header and its local file header; see
+/// [`Zip::audit`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AuditFinding {
+    /// No local file header starts at the central directory file header's `lfh_offset`
+    MissingLocalFile { offset: u64, name: String },
+
+    /// The local file name differs from the central directory file name
+    NameMismatch {
+        offset: u64,
+        local: String,
+        central: String,
+    },
+
+    /// The local file compression method differs from the central directory compression method
+    /// (e.g. an item that claims to be stored but is deflated, to confuse scanners)
+    CompressionMismatch {
+        offset: u64,
+        name: String,
+        local: u16,
+        central: u16,
+    },
+}
+
+impl std::fmt::Display for AuditFinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            AuditFinding::MissingLocalFile { offset, name } => {
+                write!(f, "No local file header at offset {offset} for `{name}`")
+            }
+            AuditFinding::NameMismatch {
+                offset,
+                local,
+                central,
+            } => write!(
+                f,
+                "Local file name `{local}` at offset {offset} differs from central directory \
+                file name `{central}`",
+            ),
+            AuditFinding::CompressionMismatch {
+                offset,
+                name,
+                local,
+                central,
+            } => write!(
+                f,
+                "Local file `{name}` at offset {offset} uses compression method {local} ({}) but \
+                the central directory file header says {central} ({})",
+                compression_name(*local),
+                compression_name(*central),
+            ),
+        }
+    }
+}
+
+impl Zip {
+    /// Compare each central directory file header with the local file header it points to
+    ///
+    /// Pairs central directory file headers with local files via `lfh_offset` and reports
+    /// central directory entries without a local file, and entries whose local file name or
+    /// compression method differs from the central directory file header (tricks to make an
+    /// archive appear to contain different files than it extracts).
+    pub fn audit(&self) -> Vec<AuditFinding> {
+        let mut findings = vec![];
+        for i in self.central_directory() {
+            let offset = self.base_offset() + i.lfh_offset() as u64;
+            let central = i.name(self.encoding()).into_owned();
+            match self.local_file_for(i) {
+                Some(local) => {
+                    if local.file_name() != i.file_name() {
+                        findings.push(AuditFinding::NameMismatch {
+                            offset,
+                            local: local.name(self.encoding()).into_owned(),
+                            central: central.clone(),
+                        });
+                    }
+                    if local.compression() != i.compression() {
+                        findings.push(AuditFinding::CompressionMismatch {
+                            offset,
+                            name: central,
+                            local: local.compression(),
+                            central: i.compression(),
+                        });
+                    }
+                }
+                None => findings.push(AuditFinding::MissingLocalFile {
+                    offset,
+                    name: central,
+                }),
+            }
+        }
+        findings
+    }
+}
@@ -0,0 +1,183 @@
+use crate::*;
+
+/// Interoperability problem found in a zip file; see [`Zip::check`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheckFinding {
+    /// General purpose bit flag 3 is set but the file data is followed by another record instead
+    /// of a data descriptor
+    MissingDataDescriptor { offset: u64, name: String },
+
+    /// The file data is followed by a data descriptor but general purpose bit flag 3 is not set
+    UnflaggedDataDescriptor { offset: u64, name: String },
+
+    /// General purpose bit flag 11 (UTF-8) is set but the file name is not valid UTF-8; `name` is
+    /// the raw file name in hex
+    InvalidUtf8Name { offset: u64, name: String },
+
+    /// The file name is valid non-ASCII UTF-8 but general purpose bit flag 11 (UTF-8) is not set,
+    /// so readers may decode it as CP437; `name` is the raw file name in hex
+    UnflaggedUtf8Name { offset: u64, name: String },
+
+    /// The end of central directory record's `cd_offset` differs from the actual offset of the
+    /// central directory (both relative to the [base offset](Zip::base_offset)); `stub` is the
+    /// base offset if it accounts for the difference, as in a self-extracting archive
+    CdOffsetMismatch {
+        declared: u64,
+        actual: u64,
+        stub: Option<u64>,
+    },
+
+    /// A central directory file header's `lfh_offset` is lower than the previous one's, which may
+    /// indicate entries that were appended or modified after the zip file was written
+    LfhOffsetOrder {
+        name: String,
+        lfh_offset: u32,
+        previous: String,
+        previous_lfh_offset: u32,
+    },
+}
+
+impl std::fmt::Display for CheckFinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CheckFinding::MissingDataDescriptor { offset, name } => write!(
+                f,
+                "Local file `{name}` at offset {offset} sets the data descriptor flag but has no \
+                data descriptor",
+            ),
+            CheckFinding::UnflaggedDataDescriptor { offset, name } => write!(
+                f,
+                "Local file `{name}` at offset {offset} has a data descriptor but does not set \
+                the data descriptor flag",
+            ),
+            CheckFinding::InvalidUtf8Name { offset, name } => write!(
+                f,
+                "File name `{name}` (hex) at offset {offset} sets the UTF-8 flag but is not valid \
+                UTF-8",
+            ),
+            CheckFinding::UnflaggedUtf8Name { offset, name } => write!(
+                f,
+                "File name `{name}` (hex) at offset {offset} is UTF-8 but does not set the UTF-8 \
+                flag",
+            ),
+            CheckFinding::CdOffsetMismatch {
+                declared,
+                actual,
+                stub,
+            } => {
+                write!(
+                    f,
+                    "End of central directory record declares the central directory at offset \
+                    {declared} but it starts at offset {actual}",
+                )?;
+                if let Some(stub) = stub {
+                    write!(
+                        f,
+                        " (the difference is the {stub}-byte prefix; likely a self-extracting \
+                        archive rather than corruption)",
+                    )?;
+                }
+                Ok(())
+            }
+            CheckFinding::LfhOffsetOrder {
+                name,
+                lfh_offset,
+                previous,
+                previous_lfh_offset,
+            } => write!(
+                f,
+                "Central directory file header `{name}` has lfh_offset {lfh_offset}, lower than \
+                {previous_lfh_offset} of the previous header `{previous}`",
+            ),
+        }
+    }
+}
+
+impl LocalFile {
+    /// Finding if the data descriptor flag disagrees with what follows the file data of the local
+    /// file at `offset`; see [`LocalFile::follows_data_descriptor`]
+    pub(crate) fn data_descriptor_finding(
+        &self,
+        offset: u64,
+        encoding: Encoding,
+    ) -> Option<CheckFinding> {
+        let follows = self.follows_data_descriptor()?;
+        if follows == self.has_data_descriptor_flag() {
+            return None;
+        }
+        let name = self.name(encoding).into_owned();
+        Some(if follows {
+            CheckFinding::UnflaggedDataDescriptor { offset, name }
+        } else {
+            CheckFinding::MissingDataDescriptor { offset, name }
+        })
+    }
+}
+
+impl Zip {
+    /// Check the records for inconsistencies that trip up strict readers
+    ///
+    /// Reports local files whose data descriptor flag (general purpose bit flag 3) disagrees
+    /// with what follows the file data (see [`LocalFile::follows_data_descriptor`]).
+    /// The flag decides how a local file is read, so the record after such a local file is
+    /// unreadable: parse leniently (see [`Zip::process_lenient`]) to keep the local file and
+    /// check it.
+    /// Also reports local and central directory file names whose UTF-8 flag (general purpose bit
+    /// flag 11) disagrees with their bytes (ASCII file names without the UTF-8 flag are not
+    /// reported), an end of central directory record whose `cd_offset` does not point to the
+    /// central directory, which indicates prepended data or corruption, and central directory
+    /// file headers whose `lfh_offset` decreases from the previous one.
+    pub fn check(&self) -> Vec<CheckFinding> {
+        let mut findings = vec![];
+        for (offset, entry) in self.entries_with_offsets() {
+            if let Entry::LocalFile(i) = entry {
+                findings.extend(i.data_descriptor_finding(offset, self.encoding()));
+            }
+            let utf8 = match entry {
+                Entry::LocalFile(i) => Some((i.file_name(), i.has_utf8_flag())),
+                Entry::CentralDirectoryFileHeader(i) => Some((i.file_name(), i.has_utf8_flag())),
+                _ => None,
+            };
+            if let Some((bytes, flag)) = utf8 {
+                let valid = std::str::from_utf8(bytes).is_ok();
+                let name = hex::encode(bytes);
+                if flag && !valid {
+                    findings.push(CheckFinding::InvalidUtf8Name { offset, name });
+                } else if !flag && valid && !bytes.is_ascii() {
+                    findings.push(CheckFinding::UnflaggedUtf8Name { offset, name });
+                }
+            }
+        }
+        if let (Some(eocd), Some(actual)) = (self.end_of_central_directory(), self.cd_start()) {
+            let declared = eocd.cd_offset() as u64;
+            if declared != actual {
+                let base = self.base_offset();
+                let stub = if base > 0 && declared.abs_diff(actual) == base {
+                    Some(base)
+                } else {
+                    None
+                };
+                findings.push(CheckFinding::CdOffsetMismatch {
+                    declared,
+                    actual,
+                    stub,
+                });
+            }
+        }
+        let mut previous: Option<&CentralDirectoryFileHeader> = None;
+        for i in self.central_directory() {
+            if let Some(p) = previous {
+                if i.lfh_offset() < p.lfh_offset() {
+                    findings.push(CheckFinding::LfhOffsetOrder {
+                        name: i.name(self.encoding()).into_owned(),
+                        lfh_offset: i.lfh_offset(),
+                        previous: p.name(self.encoding()).into_owned(),
+                        previous_lfh_offset: p.lfh_offset(),
+                    });
+                }
+            }
+            previous = Some(i);
+        }
+        findings
+    }
+}
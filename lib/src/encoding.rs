@@ -0,0 +1,104 @@
+use crate::*;
+
+/// Character encoding of file names and comments
+///
+/// Every encoding except [`Encoding::Utf8`] honors the language encoding flag (general purpose
+/// bit flag 11), which marks a name or comment as UTF-8.
+/// Select one via [`Zip::with_encoding`] or parse one from a name via [`str::parse`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// UTF-8 if the language encoding flag is set or the bytes are valid UTF-8, otherwise CP437
+    /// (default)
+    Auto,
+
+    /// UTF-8; invalid sequences are replaced with `U+FFFD`
+    Utf8,
+
+    /// IBM code page 437, the original zip file encoding
+    Cp437,
+
+    /// Any encoding supported by [`encoding_rs`](https://crates.io/crates/encoding_rs), such as
+    /// Shift-JIS, GBK, or EUC-KR (`encoding` feature)
+    #[cfg(feature = "encoding")]
+    Legacy(&'static encoding_rs::Encoding),
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Encoding::Auto
+    }
+}
+
+impl std::str::FromStr for Encoding {
+    type Err = String;
+
+    /// Parse an encoding name: `auto`, `utf-8`, `cp437`, or (with the `encoding` feature) any
+    /// [WHATWG encoding label](https://encoding.spec.whatwg.org/#names-and-labels), such as
+    /// `shift_jis`, `gbk`, or `euc-kr`
+    fn from_str(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "auto" => Ok(Encoding::Auto),
+            "utf-8" | "utf8" => Ok(Encoding::Utf8),
+            "cp437" | "ibm437" => Ok(Encoding::Cp437),
+            #[cfg(feature = "encoding")]
+            label => match encoding_rs::Encoding::for_label(label.as_bytes()) {
+                Some(encoding) => Ok(Encoding::Legacy(encoding)),
+                None => Err(unknown_encoding(name)),
+            },
+            #[cfg(not(feature = "encoding"))]
+            _ => Err(unknown_encoding(name)),
+        }
+    }
+}
+
+/// Error message for an unknown encoding name
+fn unknown_encoding(name: &str) -> String {
+    if cfg!(feature = "encoding") {
+        format!(
+            "Unknown encoding: `{name}` (expected `auto`, `utf-8`, `cp437`, or an encoding label \
+            such as `shift_jis`, `gbk`, or `euc-kr`)",
+        )
+    } else {
+        format!("Unknown encoding: `{name}` (expected `auto`, `utf-8`, or `cp437`)")
+    }
+}
+
+impl Encoding {
+    /// Decode a file name or comment of an item with general purpose bit flags `flags`
+    pub fn decode<'a>(&self, bytes: &'a [u8], flags: u16) -> Cow<'a, str> {
+        let utf8 = flags & (1 << 11) != 0;
+        match self {
+            Encoding::Utf8 => decode(bytes),
+            _ if utf8 => decode(bytes),
+            Encoding::Auto => match std::str::from_utf8(bytes) {
+                Ok(s) => Cow::Borrowed(s),
+                Err(_) => Cow::Owned(cp437(bytes)),
+            },
+            Encoding::Cp437 => Cow::Owned(cp437(bytes)),
+            #[cfg(feature = "encoding")]
+            Encoding::Legacy(encoding) => encoding.decode_without_bom_handling(bytes).0,
+        }
+    }
+}
+
+/// Decode CP437 bytes; bytes below `0x80` are decoded as ASCII
+pub fn cp437(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| match b {
+            0..=0x7f => *b as char,
+            _ => CP437[(b - 0x80) as usize],
+        })
+        .collect()
+}
+
+/// Characters for the CP437 bytes `0x80..=0xff`
+const CP437: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å', 'É', 'æ', 'Æ',
+    'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ', 'á', 'í', 'ó', 'ú', 'ñ', 'Ñ',
+    'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»', '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕',
+    '╣', '║', '╗', '╝', '╜', '╛', '┐', '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦',
+    '╠', '═', '╬', '╧', '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐',
+    '▀', 'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩', '≡', '±',
+    '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{a0}',
+];
@@ -0,0 +1,58 @@
+use crate::*;
+
+/// Encryption method of an item; see [`CentralDirectoryFileHeader::encryption`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encryption {
+    /// Not encrypted
+    None,
+
+    /// Traditional PKWARE encryption
+    ZipCrypto,
+
+    /// WinZip AES encryption with a `bits`-bit key (0 if the key strength is unknown)
+    Aes { bits: u16 },
+
+    /// PKWARE strong encryption (general purpose bit flag 6), which is not supported
+    Strong,
+}
+
+impl std::fmt::Display for Encryption {
+    /// `none`, `ZipCrypto`, `AES-<bits>`, or `strong`
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Encryption::None => write!(f, "none"),
+            Encryption::ZipCrypto => write!(f, "ZipCrypto"),
+            Encryption::Aes { bits } => write!(f, "AES-{bits}"),
+            Encryption::Strong => write!(f, "strong"),
+        }
+    }
+}
+
+impl CentralDirectoryFileHeader {
+    /// Encryption method, from general purpose bit flags 0 and 6 and the AES encryption
+    /// (`0x9901`) extra field
+    pub fn encryption(&self) -> Encryption {
+        if !self.is_encrypted() {
+            return Encryption::None;
+        }
+        if self.has_strong_encryption_flag() {
+            return Encryption::Strong;
+        }
+        let aes = self
+            .extra_fields()
+            .unwrap_or_default()
+            .into_iter()
+            .find(|i| i.tag == 0x9901);
+        match aes {
+            Some(i) => Encryption::Aes {
+                bits: match i.data.get(4) {
+                    Some(1) => 128,
+                    Some(2) => 192,
+                    Some(3) => 256,
+                    _ => 0,
+                },
+            },
+            None => Encryption::ZipCrypto,
+        }
+    }
+}
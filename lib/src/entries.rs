@@ -1,23 +1,35 @@
 use crate::*;
+use std::fmt::Write;
 
 /// Zip file
+///
+/// Reading takes the allocation limit for streamed file data (`None` for no limit); see
+/// [`ZipReader::max_alloc`].
 #[derive(BinRead, Debug)]
+#[br(import(max_alloc: Option<u64>))]
 pub struct Entries {
-    #[br(parse_with = until_eof)]
+    #[br(parse_with = until_eof, args(max_alloc))]
     pub list: Vec<Entry>,
 }
 
 /// Zip file entry
+///
+/// Reading takes the allocation limit for streamed file data, like [`Entries`].
 #[derive(BinRead, Debug)]
+#[br(import(max_alloc: Option<u64>))]
 pub enum Entry {
-    LocalFile(LocalFile),
+    LocalFile(#[br(args(max_alloc))] LocalFile),
+    ArchiveExtraDataRecord(ArchiveExtraDataRecord),
     CentralDirectoryFileHeader(CentralDirectoryFileHeader),
     EndOfCentralDirectoryRecord(EndOfCentralDirectoryRecord),
 }
 
 /// Local file header, file data, and data descriptor
-#[derive(BinRead, Debug)]
-#[br(magic = b"\x50\x4b\x03\x04")]
+///
+/// Writing a local file writes only its header; see [`LocalFile::header_bytes`].
+#[derive(BinRead, BinWrite, Debug)]
+#[brw(magic = b"\x50\x4b\x03\x04")]
+#[br(import(max_alloc: Option<u64>))]
 pub struct LocalFile {
     version: u16,
     flags: u16,
@@ -36,24 +48,166 @@ pub struct LocalFile {
     #[br(count = extra_field_length)]
     extra_field: Vec<u8>,
 
-    #[br(count = compressed_size)]
+    #[br(parse_with = read_file_data, args(flags, compressed_size, max_alloc))]
+    #[bw(ignore)]
     file_data: Vec<u8>,
 
-    #[br(if(flags & (1 << 3) != 0))]
+    /// Signature after the file data (not consumed), to report a data descriptor flag mismatch;
+    /// see [`LocalFile::follows_data_descriptor`]
+    #[br(try, restore_position)]
+    #[bw(ignore)]
+    next_signature: Option<[u8; 4]>,
+
+    #[br(if(has_data_descriptor(flags)))]
+    #[bw(ignore)]
     data_descriptor: Option<DataDescriptor>,
 }
 
 /// Data descriptor
 #[derive(BinRead, Debug)]
 pub struct DataDescriptor {
+    #[br(try)]
+    signature: Option<DataDescriptorSignature>,
+
     crc32: u32,
     compressed_size: u32,
     uncompressed_size: u32,
 }
 
-/// Central directory file header
+/// Optional data descriptor signature
 #[derive(BinRead, Debug)]
-#[br(magic = b"\x50\x4b\x01\x02")]
+#[br(magic = b"\x50\x4b\x07\x08")]
+pub struct DataDescriptorSignature;
+
+/// Whether a data descriptor follows the file data: general purpose bit flag 3 is set
+///
+/// Some tools write a data descriptor without setting the flag or set the flag without writing
+/// one; the flag still decides how the local file is read, and the mismatch is reported by
+/// [`Zip::check`].
+fn has_data_descriptor(flags: u16) -> bool {
+    flags & (1 << 3) != 0
+}
+
+/// File name and extra field lengths (at offsets 26 and 28) in the fixed-size part `b` of a local
+/// file header, or `None` if `b` is too short
+pub(crate) fn local_header_lengths(b: &[u8]) -> Option<(u16, u16)> {
+    let u16_at = |n: usize| b.get(n..n + 2).map(|x| u16::from_le_bytes([x[0], x[1]]));
+    Some((u16_at(26)?, u16_at(28)?))
+}
+
+/// Offset of the file data from the start of a local file header with the given file name and
+/// extra field lengths: past the 30-byte fixed-size part and the variable-length fields
+pub(crate) fn local_data_offset(file_name_length: u16, extra_field_length: u16) -> u64 {
+    30 + file_name_length as u64 + extra_field_length as u64
+}
+
+/// Read the file data of a local file: `compressed_size` bytes, or, if the data descriptor flag
+/// is set and `compressed_size` is zero (streamed zip files), up to the data descriptor
+///
+/// A streamed local file header does not declare the size of its file data, so the data is
+/// read ahead until [`streamed_data_length`] finds its end; the bytes are read once and the
+/// reader is left at the end of the file data.
+/// If no end is found, the file data is empty, as declared.
+///
+/// Limits of this heuristic:
+///
+/// * A false match in the file data ends it early; see [`streamed_data_length`]
+/// * The data is read ahead in 64 KiB chunks, so the scan fails once it has read more than
+///   `max_alloc` bytes without finding the end (or finds an end past `max_alloc`), instead of
+///   reading the rest of the file into memory; up to 64 KiB more than the limit may be read
+///   before it is noticed
+fn read_file_data<R>(
+    r: &mut R,
+    _: &ReadOptions,
+    (flags, compressed_size, max_alloc): (u16, u32, Option<u64>),
+) -> BinResult<Vec<u8>>
+where
+    R: Read + Seek,
+{
+    let mut data = vec![];
+    if flags & (1 << 3) == 0 || compressed_size != 0 {
+        r.by_ref()
+            .take(compressed_size as u64)
+            .read_to_end(&mut data)?;
+        if data.len() != compressed_size as usize {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+        }
+        return Ok(data);
+    }
+    let start = r.stream_position()?;
+    let max_alloc = max_alloc.unwrap_or(u64::MAX);
+    let mut from = 0;
+    loop {
+        let n = r.by_ref().take(64 * 1024).read_to_end(&mut data)?;
+        let length = streamed_data_length(&data, from);
+        if let Some(length) = length {
+            data.truncate(length);
+        }
+        if data.len() as u64 > max_alloc {
+            return Err(Error::AssertFail {
+                pos: start,
+                message: format!(
+                    "Streamed file data at offset {start} exceeds the allocation limit of \
+                    {max_alloc} bytes",
+                ),
+            });
+        }
+        if length.is_some() {
+            break;
+        }
+        if n == 0 {
+            data.clear();
+            break;
+        }
+        // Recheck the positions that did not have enough bytes after them
+        from = data.len().saturating_sub(11);
+    }
+    r.seek(SeekFrom::Start(start + data.len() as u64))?;
+    Ok(data)
+}
+
+/// Length of the file data of a streamed local file, given the bytes after its header
+///
+/// Returns the first position, starting at `from`, where either:
+///
+/// * a data descriptor signature (`PK\x07\x08`) is followed by a compressed size equal to the
+///   number of bytes before it, or
+/// * a local file header, archive extra data record, central directory file header, or end of
+///   central directory record signature is preceded by a 12-byte data descriptor without a
+///   signature whose compressed size equals the number of bytes before the data descriptor.
+///
+/// Requiring the compressed size to match makes a false match in the file data very unlikely,
+/// but not impossible.
+/// Zip64 data descriptors (8-byte sizes) and encrypted central directories are not supported.
+fn streamed_data_length(b: &[u8], from: usize) -> Option<usize> {
+    let u32_at = |i: usize| {
+        b.get(i..i + 4)
+            .map(|x| u32::from_le_bytes([x[0], x[1], x[2], x[3]]) as usize)
+    };
+    (from..b.len().saturating_sub(3)).find_map(|i| match &b[i..i + 4] {
+        b"PK\x07\x08" if u32_at(i + 8) == Some(i) => Some(i),
+        b"PK\x03\x04" | b"PK\x06\x08" | b"PK\x01\x02" | b"PK\x05\x06"
+            if i >= 12 && u32_at(i - 8) == Some(i - 12) =>
+        {
+            Some(i - 12)
+        }
+        _ => None,
+    })
+}
+
+/// Archive extra data record
+#[derive(BinRead, BinWrite, Debug)]
+#[brw(magic = b"\x50\x4b\x06\x08")]
+pub struct ArchiveExtraDataRecord {
+    extra_field_length: u32,
+
+    #[br(count = extra_field_length)]
+    extra_field: Vec<u8>,
+}
+
+/// Central directory file header
+#[derive(BinRead, BinWrite, Debug)]
+#[brw(magic = b"\x50\x4b\x01\x02")]
 pub struct CentralDirectoryFileHeader {
     version: u16,
     version_needed: u16,
@@ -83,8 +237,8 @@ pub struct CentralDirectoryFileHeader {
 }
 
 /// End of central directory record
-#[derive(BinRead, Debug)]
-#[br(magic = b"\x50\x4b\x05\x06")]
+#[derive(BinRead, BinWrite, Debug)]
+#[brw(magic = b"\x50\x4b\x05\x06")]
 pub struct EndOfCentralDirectoryRecord {
     disk_number: u16,
     disk_number_w_cd: u16,
@@ -98,21 +252,440 @@ pub struct EndOfCentralDirectoryRecord {
     zip_file_comment: Vec<u8>,
 }
 
+// Accessor methods
+
+impl Entry {
+    /// Number of bytes the record occupies in the zip file
+    pub fn record_size(&self) -> u64 {
+        match self {
+            Entry::LocalFile(i) => i.record_size(),
+            Entry::ArchiveExtraDataRecord(i) => i.record_size(),
+            Entry::CentralDirectoryFileHeader(i) => i.record_size(),
+            Entry::EndOfCentralDirectoryRecord(i) => i.record_size(),
+        }
+    }
+
+    /// On-disk bytes of the record's header, reconstructed from the parsed fields
+    ///
+    /// Excludes the file data and data descriptor of a local file; the other records have no
+    /// file data, so their header bytes are the whole record.
+    pub fn header_bytes(&self) -> Vec<u8> {
+        match self {
+            Entry::LocalFile(i) => i.header_bytes(),
+            Entry::ArchiveExtraDataRecord(i) => i.header_bytes(),
+            Entry::CentralDirectoryFileHeader(i) => i.header_bytes(),
+            Entry::EndOfCentralDirectoryRecord(i) => i.header_bytes(),
+        }
+    }
+}
+
+/// Serialize a record (for a local file, its header) in little-endian byte order
+fn write_le_bytes<T>(record: &T) -> Vec<u8>
+where
+    T: BinWrite,
+    T::Args: Default,
+{
+    let mut w = std::io::Cursor::new(vec![]);
+    // Writing to a Vec cannot fail
+    w.write_le(record).unwrap();
+    w.into_inner()
+}
+
+impl LocalFile {
+    /// Raw file name bytes
+    pub fn file_name(&self) -> &[u8] {
+        &self.file_name
+    }
+
+    /// File name decoded with `encoding`
+    pub fn name(&self, encoding: Encoding) -> Cow<'_, str> {
+        encoding.decode(&self.file_name, self.flags)
+    }
+
+    /// Raw extra field bytes
+    pub fn extra_field(&self) -> &[u8] {
+        &self.extra_field
+    }
+
+    /// Parsed extra fields
+    pub fn extra_fields(&self) -> Result<Vec<ExtraField>, String> {
+        extra_fields(&self.extra_field)
+    }
+
+    /// Compression method
+    pub fn compression(&self) -> u16 {
+        self.compression
+    }
+
+    /// Raw (possibly compressed and/or encrypted) file data bytes
+    pub fn file_data(&self) -> &[u8] {
+        &self.file_data
+    }
+
+    /// CRC-32 of the uncompressed file data, from the data descriptor if the header field is zero
+    /// (streamed zip files)
+    pub fn crc32(&self) -> u32 {
+        match &self.data_descriptor {
+            Some(d) if self.crc32 == 0 => d.crc32,
+            _ => self.crc32,
+        }
+    }
+
+    /// Compressed size, from the data descriptor if the header field is zero (streamed zip
+    /// files)
+    pub fn compressed_size(&self) -> u32 {
+        match &self.data_descriptor {
+            Some(d) if self.compressed_size == 0 => d.compressed_size,
+            _ => self.compressed_size,
+        }
+    }
+
+    /// Uncompressed size, from the data descriptor if the header field is zero (streamed zip
+    /// files)
+    pub fn uncompressed_size(&self) -> u32 {
+        match &self.data_descriptor {
+            Some(d) if self.uncompressed_size == 0 => d.uncompressed_size,
+            _ => self.uncompressed_size,
+        }
+    }
+
+    /// Whether general purpose bit flag 3 (data descriptor follows the file data) is set
+    pub fn has_data_descriptor_flag(&self) -> bool {
+        self.flags & (1 << 3) != 0
+    }
+
+    /// Whether general purpose bit flag 11 (language encoding: file name is UTF-8) is set
+    pub fn has_utf8_flag(&self) -> bool {
+        self.flags & (1 << 11) != 0
+    }
+
+    /// Whether general purpose bit flag 6 (strong encryption) is set
+    pub fn has_strong_encryption_flag(&self) -> bool {
+        self.flags & (1 << 6) != 0
+    }
+
+    /// Whether general purpose bit flag 13 is set: the central directory is encrypted and
+    /// selected local header values are masked
+    pub fn has_masked_header_flag(&self) -> bool {
+        self.flags & (1 << 13) != 0
+    }
+
+    /// Whether the file data is followed by a data descriptor signature (`Some(true)`) or the
+    /// signature of another record (`Some(false)`), whatever the data descriptor flag
+    ///
+    /// Returns `None` for anything else, such as a data descriptor without a signature.
+    pub fn follows_data_descriptor(&self) -> Option<bool> {
+        match self.next_signature.as_ref()? {
+            b"\x50\x4b\x07\x08" => Some(true),
+            b"\x50\x4b\x03\x04" | b"\x50\x4b\x06\x08" | b"\x50\x4b\x01\x02"
+            | b"\x50\x4b\x05\x06" => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Data descriptor, if present
+    pub fn data_descriptor(&self) -> Option<&DataDescriptor> {
+        self.data_descriptor.as_ref()
+    }
+
+    /// Whether the header was written before the file data was known (streamed zip files): the
+    /// data descriptor flag is set and the header sizes are zero
+    pub fn is_streamed(&self) -> bool {
+        self.has_data_descriptor_flag() && self.compressed_size == 0 && self.uncompressed_size == 0
+    }
+
+    /// Offset of the file data from the start of the local file header, past the variable-length
+    /// file name and extra field
+    pub fn data_offset(&self) -> u64 {
+        local_data_offset(self.file_name_length, self.extra_field_length)
+    }
+
+    /// Number of bytes the header, file data, and data descriptor occupy in the zip file
+    pub fn record_size(&self) -> u64 {
+        let data_descriptor = match &self.data_descriptor {
+            Some(i) => i.record_size(),
+            None => 0,
+        };
+        (30 + self.file_name.len() + self.extra_field.len() + self.file_data.len()) as u64
+            + data_descriptor
+    }
+
+    /// On-disk bytes of the local file header, without the file data; see
+    /// [`Entry::header_bytes`]
+    pub fn header_bytes(&self) -> Vec<u8> {
+        write_le_bytes(self)
+    }
+}
+
+impl DataDescriptor {
+    /// CRC-32 of the uncompressed file data
+    pub fn crc32(&self) -> u32 {
+        self.crc32
+    }
+
+    /// Compressed size
+    pub fn compressed_size(&self) -> u32 {
+        self.compressed_size
+    }
+
+    /// Uncompressed size
+    pub fn uncompressed_size(&self) -> u32 {
+        self.uncompressed_size
+    }
+
+    /// Number of bytes the data descriptor (and its signature, if present) occupies in the zip
+    /// file
+    pub fn record_size(&self) -> u64 {
+        match &self.signature {
+            Some(_) => 16,
+            None => 12,
+        }
+    }
+}
+
+impl ArchiveExtraDataRecord {
+    /// Raw extra field bytes
+    pub fn extra_field(&self) -> &[u8] {
+        &self.extra_field
+    }
+
+    /// Parsed extra fields
+    pub fn extra_fields(&self) -> Result<Vec<ExtraField>, String> {
+        extra_fields(&self.extra_field)
+    }
+
+    /// Number of bytes the record occupies in the zip file
+    pub fn record_size(&self) -> u64 {
+        (8 + self.extra_field.len()) as u64
+    }
+
+    /// On-disk bytes of the record; see [`Entry::header_bytes`]
+    pub fn header_bytes(&self) -> Vec<u8> {
+        write_le_bytes(self)
+    }
+}
+
+impl CentralDirectoryFileHeader {
+    /// Raw file name bytes
+    pub fn file_name(&self) -> &[u8] {
+        &self.file_name
+    }
+
+    /// File name decoded with `encoding`
+    pub fn name(&self, encoding: Encoding) -> Cow<'_, str> {
+        encoding.decode(&self.file_name, self.flags)
+    }
+
+    /// File comment decoded with `encoding`
+    pub fn comment(&self, encoding: Encoding) -> Cow<'_, str> {
+        encoding.decode(&self.file_comment, self.flags)
+    }
+
+    /// Number of bytes the header occupies in the zip file
+    pub fn record_size(&self) -> u64 {
+        (46 + self.file_name.len() + self.extra_field.len() + self.file_comment.len()) as u64
+    }
+
+    /// On-disk bytes of the header; see [`Entry::header_bytes`]
+    pub fn header_bytes(&self) -> Vec<u8> {
+        write_le_bytes(self)
+    }
+
+    /// Offset of the corresponding local file header
+    pub fn lfh_offset(&self) -> u32 {
+        self.lfh_offset
+    }
+
+    /// Raw extra field bytes
+    pub fn extra_field(&self) -> &[u8] {
+        &self.extra_field
+    }
+
+    /// Parsed extra fields
+    pub fn extra_fields(&self) -> Result<Vec<ExtraField>, String> {
+        extra_fields(&self.extra_field)
+    }
+
+    /// Raw file comment bytes
+    pub fn file_comment(&self) -> &[u8] {
+        &self.file_comment
+    }
+
+    /// Whether the item is a folder
+    pub fn is_dir(&self) -> bool {
+        self.file_name.ends_with(b"/")
+    }
+
+    /// Unix file mode (type and permission bits, e.g. `0o100755`) from the high 16 bits of the
+    /// external file attributes, or `None` if the host system (the high byte of the version made
+    /// by) is not Unix (3) or OS X (19)
+    pub fn unix_mode(&self) -> Option<u32> {
+        match self.version >> 8 {
+            3 | 19 => Some(self.external_file_attributes >> 16),
+            _ => None,
+        }
+    }
+
+    /// Whether any execute bit of the [Unix mode](CentralDirectoryFileHeader::unix_mode) is set,
+    /// e.g. to review the scripts and binaries in a zip file, or `None` if the host system is not
+    /// Unix
+    ///
+    /// Folders are never executable, since their execute bits mean searchable.
+    pub fn is_executable(&self) -> Option<bool> {
+        self.unix_mode()
+            .map(|mode| !self.is_dir() && mode & 0o111 != 0)
+    }
+
+    /// Modified date/time as `YYYY-MM-DDTHH:MM:SS`
+    ///
+    /// The MS-DOS date/time is the local time where the item was added, with no time zone, so it
+    /// has no suffix; see [`CentralDirectoryFileHeader::modified_utc`] to convert it to UTC.
+    pub fn modified(&self) -> String {
+        let t = mod_time(self.mod_time).0;
+        let d = mod_date(self.mod_date).0;
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            d.0, d.1, d.2, t.0, t.1, t.2,
+        )
+    }
+
+    /// Modified date/time in UTC as `YYYY-MM-DDTHH:MM:SSZ`, assuming the local date/time was
+    /// recorded at `offset` minutes east of UTC (`0` assumes it is already UTC; see
+    /// [`dos_datetime_utc`])
+    pub fn modified_utc(&self, offset: i32) -> String {
+        dos_datetime_utc(self.mod_date, self.mod_time, offset)
+    }
+
+    /// Version needed to extract (major version * 10 + minor version)
+    pub fn version_needed(&self) -> u16 {
+        self.version_needed
+    }
+
+    /// Whether general purpose bit flag 11 (language encoding: file name and comment are UTF-8)
+    /// is set
+    pub fn has_utf8_flag(&self) -> bool {
+        self.flags & (1 << 11) != 0
+    }
+
+    /// Whether the item is encrypted (general purpose bit flag 0)
+    pub fn is_encrypted(&self) -> bool {
+        self.flags & 1 != 0
+    }
+
+    /// Whether general purpose bit flag 6 (strong encryption) is set
+    pub fn has_strong_encryption_flag(&self) -> bool {
+        self.flags & (1 << 6) != 0
+    }
+
+    /// Compression method
+    pub fn compression(&self) -> u16 {
+        self.compression
+    }
+
+    /// CRC-32 of the uncompressed file data
+    pub fn crc32(&self) -> u32 {
+        self.crc32
+    }
+
+    /// Compressed size; set even for streamed zip files, whose local file header defers it to
+    /// the data descriptor (see [`LocalFile::compressed_size`])
+    pub fn compressed_size(&self) -> u32 {
+        self.compressed_size
+    }
+
+    /// Uncompressed size; set even for streamed zip files, whose local file header defers it to
+    /// the data descriptor (see [`LocalFile::uncompressed_size`])
+    pub fn uncompressed_size(&self) -> u32 {
+        self.uncompressed_size
+    }
+}
+
+impl EndOfCentralDirectoryRecord {
+    /// Number of bytes the record occupies in the zip file
+    pub fn record_size(&self) -> u64 {
+        (22 + self.zip_file_comment.len()) as u64
+    }
+
+    /// On-disk bytes of the record; see [`Entry::header_bytes`]
+    pub fn header_bytes(&self) -> Vec<u8> {
+        write_le_bytes(self)
+    }
+
+    /// Number of this disk
+    pub fn disk_number(&self) -> u16 {
+        self.disk_number
+    }
+
+    /// Number of the disk where the central directory starts
+    pub fn disk_number_w_cd(&self) -> u16 {
+        self.disk_number_w_cd
+    }
+
+    /// Number of central directory entries on this disk
+    pub fn disk_entries(&self) -> u16 {
+        self.disk_entries
+    }
+
+    /// Total number of central directory entries
+    pub fn total_entries(&self) -> u16 {
+        self.total_entries
+    }
+
+    /// Size of the central directory
+    pub fn cd_size(&self) -> u32 {
+        self.cd_size
+    }
+
+    /// Offset of the start of the central directory
+    pub fn cd_offset(&self) -> u32 {
+        self.cd_offset
+    }
+
+    /// Raw zip file comment bytes
+    pub fn zip_file_comment(&self) -> &[u8] {
+        &self.zip_file_comment
+    }
+
+    /// Zip file comment decoded with `encoding`
+    pub fn comment(&self, encoding: Encoding) -> Cow<'_, str> {
+        encoding.decode(&self.zip_file_comment, 0)
+    }
+}
+
 // Output methods
 
 impl LocalFile {
     pub fn verbose(&self) -> String {
-        format!(
+        self.verbose_with_encoding(Encoding::default())
+    }
+
+    /// Verbose representation with the file name and comment decoded with `encoding`
+    pub fn verbose_with_encoding(&self, encoding: Encoding) -> String {
+        let mut s = String::new();
+        self.write_verbose_with_encoding(&mut s, encoding);
+        s
+    }
+
+    /// Append the verbose representation to `s`
+    pub fn write_verbose(&self, s: &mut String) {
+        self.write_verbose_with_encoding(s, Encoding::default());
+    }
+
+    /// Append the verbose representation with the file name and comment decoded with `encoding`
+    /// to `s`
+    pub fn write_verbose_with_encoding(&self, s: &mut String, encoding: Encoding) {
+        write!(
+            s,
             "\
 sig = 0x504b0304 (Local file header)
 version = 0x{:04x} ({})
 flags = 0x{:04x} ({})
 compression = 0x{:04x} ({})
-mod_time = 0x{:04x} ({:?})
-mod_date = 0x{:04x} ({:?})
-crc32 = 0x{:08x} ({})
-compressed_size = 0x{:08x} ({})
-uncompressed_size = 0x{:08x} ({})
+mod_time = 0x{:04x} ({:?}, local time, no time zone)
+mod_date = 0x{:04x} ({:?}, local time, no time zone)
+crc32 = 0x{:08x} ({}){}
+compressed_size = 0x{:08x} ({}){}
+uncompressed_size = 0x{:08x} ({}){}
 file_name_length = 0x{:04x} ({})
 extra_field_length = 0x{:04x} ({})
 file_name = {:?} ({:?})
@@ -133,23 +706,36 @@ data_descriptor = {}
             mod_date(self.mod_date).0,
             self.crc32,
             self.crc32,
+            from_data_descriptor(self.crc32, self.crc32()),
             self.compressed_size,
             self.compressed_size,
+            from_data_descriptor(self.compressed_size, self.compressed_size()),
             self.uncompressed_size,
             self.uncompressed_size,
+            from_data_descriptor(self.uncompressed_size, self.uncompressed_size()),
             self.file_name_length,
             self.file_name_length,
             self.extra_field_length,
             self.extra_field_length,
-            hex::encode(&self.file_name),
-            std::str::from_utf8(&self.file_name).unwrap(),
-            hex::encode(&self.extra_field),
-            hex::encode(&self.file_data),
+            QuotedHex(&self.file_name),
+            self.name(encoding),
+            QuotedHex(&self.extra_field),
+            QuotedHex(&self.file_data),
             match &self.data_descriptor {
                 Some(d) => d.verbose(),
                 None => String::from("None"),
             },
         )
+        .unwrap();
+    }
+}
+
+/// Annotation for a local file header field whose value comes from the data descriptor
+fn from_data_descriptor(header: u32, value: u32) -> String {
+    if header == value {
+        String::new()
+    } else {
+        format!(" [data descriptor: 0x{value:08x} ({value})]")
     }
 }
 
@@ -174,17 +760,61 @@ impl DataDescriptor {
     }
 }
 
+impl ArchiveExtraDataRecord {
+    pub fn verbose(&self) -> String {
+        let mut s = String::new();
+        self.write_verbose(&mut s);
+        s
+    }
+
+    /// Append the verbose representation to `s`
+    pub fn write_verbose(&self, s: &mut String) {
+        write!(
+            s,
+            "\
+sig = 0x504b0608 (Archive extra data record)
+extra_field_length = 0x{:08x} ({})
+extra_field = {:?}
+\
+            ",
+            self.extra_field_length,
+            self.extra_field_length,
+            QuotedHex(&self.extra_field),
+        )
+        .unwrap();
+    }
+}
+
 impl CentralDirectoryFileHeader {
     pub fn verbose(&self) -> String {
-        format!(
+        self.verbose_with_encoding(Encoding::default())
+    }
+
+    /// Verbose representation with the file name and comment decoded with `encoding`
+    pub fn verbose_with_encoding(&self, encoding: Encoding) -> String {
+        let mut s = String::new();
+        self.write_verbose_with_encoding(&mut s, encoding);
+        s
+    }
+
+    /// Append the verbose representation to `s`
+    pub fn write_verbose(&self, s: &mut String) {
+        self.write_verbose_with_encoding(s, Encoding::default());
+    }
+
+    /// Append the verbose representation with the file name and comment decoded with `encoding`
+    /// to `s`
+    pub fn write_verbose_with_encoding(&self, s: &mut String, encoding: Encoding) {
+        write!(
+            s,
             "\
 sig = 0x504b0102 (Central directory file header)
 version = 0x{:04x} ({})
 version_needed = 0x{:04x} ({})
 flags = 0x{:04x} ({})
 compression = 0x{:04x} ({})
-mod_time = 0x{:04x} ({:?})
-mod_date = 0x{:04x} ({:?})
+mod_time = 0x{:04x} ({:?}, local time, no time zone)
+mod_date = 0x{:04x} ({:?}, local time, no time zone)
 crc32 = 0x{:08x} ({})
 compressed_size = 0x{:08x} ({})
 uncompressed_size = 0x{:08x} ({})
@@ -198,6 +828,7 @@ lfh_offset = 0x{:08x} ({})
 file_name = {:?} ({:?})
 extra_field = {:?}
 file_comment = {:?} ({:?})
+encryption = {}
 \
             ",
             self.version,
@@ -232,36 +863,103 @@ file_comment = {:?} ({:?})
             self.external_file_attributes,
             self.lfh_offset,
             self.lfh_offset,
-            hex::encode(&self.file_name),
-            std::str::from_utf8(&self.file_name).unwrap(),
-            hex::encode(&self.extra_field),
-            hex::encode(&self.file_comment),
-            std::str::from_utf8(&self.file_comment).unwrap(),
+            QuotedHex(&self.file_name),
+            self.name(encoding),
+            QuotedHex(&self.extra_field),
+            QuotedHex(&self.file_comment),
+            self.comment(encoding),
+            self.encryption(),
         )
+        .unwrap();
     }
 
-    pub fn summary(&self) -> String {
-        let t = mod_time(self.mod_time).0;
-        let d = mod_date(self.mod_date).0;
-        format!(
-            "{}\t{}\t{}\t{:04}-{:02}-{:02}T{:02}:{:02}:{:02}\t{}\n",
-            std::str::from_utf8(&self.file_name).unwrap(),
-            self.file_name.ends_with(b"/"),
+    pub fn summary(&self, options: &SummaryOptions) -> String {
+        self.summary_with_encoding(Encoding::default(), options)
+    }
+
+    /// Summary line with the file name and comment decoded with `encoding`
+    pub fn summary_with_encoding(&self, encoding: Encoding, options: &SummaryOptions) -> String {
+        let mut s = String::new();
+        self.write_summary_with_encoding(&mut s, encoding, options);
+        s
+    }
+
+    /// Append the summary line to `s`
+    pub fn write_summary(&self, s: &mut String, options: &SummaryOptions) {
+        self.write_summary_with_encoding(s, Encoding::default(), options);
+    }
+
+    /// Append the summary line with the file name and comment decoded with `encoding` to `s`
+    pub fn write_summary_with_encoding(
+        &self,
+        s: &mut String,
+        encoding: Encoding,
+        options: &SummaryOptions,
+    ) {
+        self.write_summary_named(s, &self.name(encoding), encoding, options);
+    }
+
+    /// Append the summary line to `s` with the file name already decoded with `encoding`, for
+    /// [`Zip::summary_rows`], which decodes it to filter on it
+    pub(crate) fn write_summary_named(
+        &self,
+        s: &mut String,
+        name: &str,
+        encoding: Encoding,
+        options: &SummaryOptions,
+    ) {
+        let d = options.delimiter();
+        write!(
+            s,
+            "{name}{d}{}{d}{}{d}{}{d}{}",
+            self.is_dir(),
             self.uncompressed_size,
-            d.0,
-            d.1,
-            d.2,
-            t.0,
-            t.1,
-            t.2,
-            std::str::from_utf8(&self.file_comment).unwrap(),
+            options
+                .utc_offset
+                .map_or_else(|| self.modified(), |offset| self.modified_utc(offset)),
+            self.comment(encoding),
         )
+        .unwrap();
+        if options.method {
+            write!(s, "{d}{}", compression_name(self.compression)).unwrap();
+        }
+        if options.encryption {
+            write!(s, "{d}{}", self.encryption()).unwrap();
+        }
+        if options.executable {
+            let marker = if self.is_executable() == Some(true) {
+                "(exec)"
+            } else {
+                ""
+            };
+            write!(s, "{d}{marker}").unwrap();
+        }
+        s.push_str(options.separator());
     }
 }
 
 impl EndOfCentralDirectoryRecord {
     pub fn verbose(&self) -> String {
-        format!(
+        self.verbose_with_encoding(Encoding::default())
+    }
+
+    /// Verbose representation with the zip file comment decoded with `encoding`
+    pub fn verbose_with_encoding(&self, encoding: Encoding) -> String {
+        let mut s = String::new();
+        self.write_verbose_with_encoding(&mut s, encoding);
+        s
+    }
+
+    /// Append the verbose representation to `s`
+    pub fn write_verbose(&self, s: &mut String) {
+        self.write_verbose_with_encoding(s, Encoding::default());
+    }
+
+    /// Append the verbose representation with the zip file comment decoded with `encoding` to
+    /// `s`
+    pub fn write_verbose_with_encoding(&self, s: &mut String, encoding: Encoding) {
+        write!(
+            s,
             "\
 sig = 0x504b0506 (End of central directory record)
 disk_number = 0x{:04x} ({})
@@ -288,8 +986,9 @@ zip_file_comment = {:?} ({:?})
             self.cd_offset,
             self.comment_length,
             self.comment_length,
-            hex::encode(&self.zip_file_comment),
-            std::str::from_utf8(&self.zip_file_comment).unwrap(),
+            QuotedHex(&self.zip_file_comment),
+            self.comment(encoding),
         )
+        .unwrap();
     }
 }
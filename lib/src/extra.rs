@@ -0,0 +1,207 @@
+use crate::*;
+
+/// Extra field record: a header ID (tag) and its data
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExtraField {
+    pub tag: u16,
+    pub data: Vec<u8>,
+}
+
+/// Parse extra field bytes into tag/size/data records
+pub fn extra_fields(bytes: &[u8]) -> Result<Vec<ExtraField>, String> {
+    let mut r = vec![];
+    let mut rest = bytes;
+    while !rest.is_empty() {
+        if rest.len() < 4 {
+            return Err(format!(
+                "Truncated extra field header: `{}`",
+                hex::encode(rest),
+            ));
+        }
+        let tag = u16::from_le_bytes([rest[0], rest[1]]);
+        let size = u16::from_le_bytes([rest[2], rest[3]]) as usize;
+        if rest.len() < 4 + size {
+            return Err(format!(
+                "Truncated extra field 0x{tag:04x}: size is {size} but only {} bytes remain",
+                rest.len() - 4,
+            ));
+        }
+        r.push(ExtraField {
+            tag,
+            data: rest[4..4 + size].to_vec(),
+        });
+        rest = &rest[4 + size..];
+    }
+    Ok(r)
+}
+
+/// Generate a verbose representation of all extra fields in `bytes`
+pub fn extra_fields_verbose(bytes: &[u8]) -> Result<String, String> {
+    let fields = extra_fields(bytes)?;
+    if fields.is_empty() {
+        return Ok(String::from("(none)\n"));
+    }
+    Ok(fields
+        .iter()
+        .map(|i| i.verbose())
+        .collect::<Vec<String>>()
+        .join("---\n"))
+}
+
+/// Name of a well-known extra field header ID
+pub fn extra_field_name(tag: u16) -> &'static str {
+    match tag {
+        0x0001 => "Zip64 extended information",
+        0x0007 => "AV Info",
+        0x000a => "NTFS",
+        0x000d => "UNIX",
+        0x0017 => "Strong encryption header",
+        0x5455 => "Extended timestamp",
+        0x5855 => "Info-ZIP UNIX (original)",
+        0x6375 => "Info-ZIP Unicode comment",
+        0x7075 => "Info-ZIP Unicode path",
+        0x7855 => "Info-ZIP UNIX (previous new)",
+        0x7875 => "Info-ZIP UNIX (new)",
+        0x9901 => "AES encryption",
+        _ => "Unknown",
+    }
+}
+
+impl ExtraField {
+    /// Interpret the data of well-known extra fields as `(name, value)` pairs
+    ///
+    /// Returns an empty list for unknown extra fields and stops at the first value that doesn't
+    /// fit in the data.
+    pub fn interpret(&self) -> Vec<(&'static str, String)> {
+        let d = &self.data;
+        let u8_at = |i: usize| d.get(i).copied();
+        let u16_at = |i: usize| d.get(i..i + 2).map(|b| u16::from_le_bytes([b[0], b[1]]));
+        let u32_at = |i: usize| {
+            d.get(i..i + 4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        };
+        let u64_at = |i: usize| {
+            d.get(i..i + 8).map(|b| {
+                let mut a = [0; 8];
+                a.copy_from_slice(b);
+                u64::from_le_bytes(a)
+            })
+        };
+        let mut r = vec![];
+        match self.tag {
+            // Zip64 extended information: up to four 64-bit sizes/offsets
+            0x0001 => {
+                for (n, name) in ["value_0", "value_1", "value_2", "value_3"]
+                    .iter()
+                    .enumerate()
+                {
+                    if let Some(v) = u64_at(n * 8) {
+                        r.push((*name, v.to_string()));
+                    }
+                }
+            }
+
+            // NTFS: reserved, then attribute tag 1 with modified/accessed/created FILETIMEs
+            0x000a => {
+                if u16_at(4) == Some(1) {
+                    for (n, name) in ["mtime", "atime", "ctime"].iter().enumerate() {
+                        if let Some(v) = u64_at(8 + n * 8) {
+                            r.push((*name, filetime(v)));
+                        }
+                    }
+                }
+            }
+
+            // Extended timestamp: flags, then the Unix times indicated by the flags
+            0x5455 => {
+                if let Some(flags) = u8_at(0) {
+                    r.push(("flags", format!("0x{flags:02x}")));
+                    let mut i = 1;
+                    for (bit, name) in ["mtime", "atime", "ctime"].iter().enumerate() {
+                        if flags & (1 << bit) != 0 {
+                            if let Some(v) = u32_at(i) {
+                                r.push((*name, unix_datetime(v as i32 as i64)));
+                            }
+                            i += 4;
+                        }
+                    }
+                }
+            }
+
+            // Info-ZIP UNIX (new): version, UID size, UID, GID size, GID
+            0x7875 => {
+                if let (Some(version), Some(uid_size)) = (u8_at(0), u8_at(1)) {
+                    r.push(("version", version.to_string()));
+                    let uid_size = uid_size as usize;
+                    let gid_size = u8_at(2 + uid_size).map(|n| n as usize);
+                    let id = |i: usize, size: usize| {
+                        d.get(i..i + size)
+                            .filter(|b| b.len() <= 8)
+                            .map(|b| b.iter().rev().fold(0u64, |n, byte| (n << 8) | *byte as u64))
+                    };
+                    if let Some(uid) = id(2, uid_size) {
+                        r.push(("uid", uid.to_string()));
+                    }
+                    if let Some(gid) = gid_size.and_then(|size| id(3 + uid_size, size)) {
+                        r.push(("gid", gid.to_string()));
+                    }
+                }
+            }
+
+            // Info-ZIP Unicode path/comment: version, CRC-32 of the header field, UTF-8 value
+            0x6375 | 0x7075 => {
+                if let (Some(version), Some(crc32)) = (u8_at(0), u32_at(1)) {
+                    r.push(("version", version.to_string()));
+                    r.push(("crc32", format!("0x{crc32:08x}")));
+                    r.push(("value", format!("{:?}", decode(&d[5..]))));
+                }
+            }
+
+            // AES encryption: vendor version, vendor ID, key strength, actual compression
+            0x9901 => {
+                if let Some(version) = u16_at(0) {
+                    r.push(("vendor_version", format!("AE-{version}")));
+                }
+                if let Some(vendor) = d.get(2..4) {
+                    r.push(("vendor_id", format!("{:?}", decode(vendor))));
+                }
+                if let Some(strength) = u8_at(4) {
+                    let bits = match strength {
+                        1 => "128",
+                        2 => "192",
+                        3 => "256",
+                        _ => "unknown",
+                    };
+                    r.push(("strength", format!("{strength} ({bits}-bit)")));
+                }
+                if let Some(compression) = u16_at(5) {
+                    r.push(("compression", compression.to_string()));
+                }
+            }
+
+            _ => {}
+        }
+        r
+    }
+
+    /// Generate a verbose representation with the raw data and interpreted values
+    pub fn verbose(&self) -> String {
+        let mut s = format!(
+            "\
+tag = 0x{:04x} ({})
+size = 0x{:04x} ({})
+data = {:?}
+\
+            ",
+            self.tag,
+            extra_field_name(self.tag),
+            self.data.len(),
+            self.data.len(),
+            QuotedHex(&self.data),
+        );
+        for (name, value) in self.interpret() {
+            s.push_str(&format!("{name} = {value}\n"));
+        }
+        s
+    }
+}
@@ -0,0 +1,234 @@
+use crate::*;
+
+/// Compression methods that [`decompress`] supports with the enabled features: stored (0) and,
+/// with the `deflate` feature (enabled by default), deflated (8)
+pub fn supported_compression_methods() -> &'static [u16] {
+    if cfg!(feature = "deflate") {
+        &[0, 8]
+    } else {
+        &[0]
+    }
+}
+
+/// Decompress file data stored with compression method `compression`
+///
+/// Supports the methods listed by [`supported_compression_methods`].
+/// Deflate64 (9) is rejected with its own error: it looks like deflate but has a 64 KiB window,
+/// two more distance codes, and a different length code 285, so a standard deflate decoder could
+/// produce corrupt data instead of an error.
+/// Decompression stops after `uncompressed_size` bytes (the declared uncompressed size) and fails
+/// if there is more, so a forged size cannot make a small item exhaust memory.
+pub fn decompress(
+    compression: u16,
+    data: &[u8],
+    uncompressed_size: u64,
+) -> Result<Vec<u8>, String> {
+    if compression == 9 {
+        return Err(String::from("Deflate64 is not supported"));
+    }
+    if !supported_compression_methods().contains(&compression) {
+        return Err(format!("Unsupported compression method: {compression}"));
+    }
+    match compression {
+        0 => Ok(data.to_vec()),
+        #[cfg(feature = "deflate")]
+        8 => {
+            let mut r = vec![];
+            flate2::read::DeflateDecoder::new(data)
+                .take(uncompressed_size.saturating_add(1))
+                .read_to_end(&mut r)
+                .map_err(|e| e.to_string())?;
+            if r.len() as u64 > uncompressed_size {
+                return Err(format!(
+                    "Decompressed data expands past its declared size of {uncompressed_size} bytes",
+                ));
+            }
+            Ok(r)
+        }
+        _ => Err(format!("Unsupported compression method: {compression}")),
+    }
+}
+
+/// Check that an item name is safe to extract and convert it into a relative path
+///
+/// Rejects names that could write outside the destination folder ("zip slip"): absolute paths,
+/// drive letter (`C:`) and UNC (`\\server`) prefixes, and any `..` component.
+/// Any component with a `:` is rejected too: on Windows, a `C:` or `C:x` component (as in
+/// `a/C:/x`) replaces the path built so far, and `b:stream` names an alternate data stream.
+/// Both `/` and `\` are treated as separators; empty and `.` components are dropped.
+pub fn is_safe_path(name: &str) -> Result<PathBuf, String> {
+    if name.starts_with('/') || name.starts_with('\\') {
+        return Err(format!("Absolute path: `{name}`"));
+    }
+    let bytes = name.as_bytes();
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        return Err(format!("Drive letter prefix: `{name}`"));
+    }
+    let mut path = PathBuf::new();
+    for component in name.split(['/', '\\']) {
+        match component {
+            "" | "." => {}
+            ".." => return Err(format!("Path traversal: `{name}`")),
+            _ if component.contains(':') => return Err(format!("Colon in path: `{name}`")),
+            _ if Path::new(component).is_absolute() || Path::new(component).has_root() => {
+                return Err(format!("Absolute path: `{name}`"));
+            }
+            _ => path.push(component),
+        }
+    }
+    if path.as_os_str().is_empty() {
+        return Err(format!("Empty path: `{name}`"));
+    }
+    Ok(path)
+}
+
+impl Zip {
+    /// Decompressed data of the first item named `name`; see [`decompress`]
+    ///
+    /// Fails if the CRC-32 of the decompressed data differs from the central directory file
+    /// header's.
+    pub fn extract(&self, name: &str) -> Result<Vec<u8>, String> {
+        let header = self
+            .find(name)
+            .ok_or_else(|| format!("Item not found: `{name}`"))?;
+        if header.is_dir() {
+            return Err(format!("Item is a folder: `{name}`"));
+        }
+        self.extract_file(header)
+    }
+
+    /// Decompressed and CRC-32 checked data of the file of central directory file header `header`
+    /// for [`Zip::extract`] and [`Zip::sbom`]
+    pub(crate) fn extract_file(
+        &self,
+        header: &CentralDirectoryFileHeader,
+    ) -> Result<Vec<u8>, String> {
+        let name = header.name(self.encoding);
+        if header.is_encrypted() {
+            return Err(format!("Item is encrypted: `{name}`"));
+        }
+        let local = self
+            .local_file_for(header)
+            .ok_or_else(|| format!("No local file header for `{name}`"))?;
+        let size = u64::from(header.uncompressed_size());
+        let data = decompress(local.compression(), local.file_data(), size)?;
+        let computed = crc32(&data);
+        if computed != header.crc32() {
+            return Err(format!(
+                "CRC-32 mismatch for `{name}`: expected 0x{:08x}, computed 0x{computed:08x}",
+                header.crc32(),
+            ));
+        }
+        Ok(data)
+    }
+
+    /// Write the decompressed data of the first item named `name` to `w`, like `unzip -p`; see
+    /// [`Zip::extract`]
+    pub fn extract_to<W: std::io::Write>(&self, name: &str, w: &mut W) -> Result<(), String> {
+        let data = self.extract(name)?;
+        w.write_all(&data).map_err(|e| e.to_string())
+    }
+
+    /// Plan an extraction into `dest` without touching the filesystem
+    ///
+    /// Returns each item name with the path it would be written to, in central directory order.
+    /// Folders without an entry of their own (see [`Zip::implied_directories`]) come first, so
+    /// that they are created before the items they contain.
+    /// Names that are not safe to extract are left out; see [`Zip::unsafe_paths`].
+    pub fn extract_plan(&self, dest: &Path) -> Vec<(String, PathBuf)> {
+        self.extract_targets()
+            .filter_map(|(name, _)| {
+                let path = is_safe_path(&name).ok()?;
+                Some((name, dest.join(path)))
+            })
+            .collect()
+    }
+
+    /// Item names that are not safe to extract, with why (see [`is_safe_path`]), in the order of
+    /// [`Zip::extract_plan`]
+    pub fn unsafe_paths(&self) -> Vec<(String, String)> {
+        self.extract_targets()
+            .filter_map(|(name, _)| Some((name.clone(), is_safe_path(&name).err()?)))
+            .collect()
+    }
+
+    /// Extract all items into `dest`, like `unzip -d`, and return the [plan](Zip::extract_plan)
+    ///
+    /// Nothing is written if any item name is [unsafe](Zip::unsafe_paths) or two items would be
+    /// written to the same path.
+    /// Folders (including implied folders) are created as needed, existing files are
+    /// overwritten, and the data of each file is checked as in [`Zip::extract`].
+    /// Extraction stops at the first error, so a CRC-32 mismatch can leave the earlier items in
+    /// place.
+    pub fn extract_all(&self, dest: &Path) -> Result<Vec<(String, PathBuf)>, String> {
+        if let Some((_, e)) = self.unsafe_paths().into_iter().next() {
+            return Err(e);
+        }
+        let plan = self.extract_plan(dest);
+        let mut seen = BTreeSet::new();
+        for (name, path) in &plan {
+            if !seen.insert(path) {
+                return Err(format!("Path collision: `{name}` -> `{}`", path.display()));
+            }
+        }
+        let io = |path: &Path| {
+            let path = path.display().to_string();
+            move |e: std::io::Error| format!("{e}: `{path}`")
+        };
+        for ((_, path), (_, header)) in plan.iter().zip(self.extract_targets()) {
+            match header.filter(|i| !i.is_dir()) {
+                Some(header) => {
+                    let data = self.extract_file(header)?;
+                    if let Some(parent) = path.parent() {
+                        std::fs::create_dir_all(parent).map_err(io(parent))?;
+                    }
+                    std::fs::write(path, data).map_err(io(path))?;
+                }
+                None => std::fs::create_dir_all(path).map_err(io(path))?,
+            }
+        }
+        Ok(plan)
+    }
+
+    /// Implied folder names, then central directory file names with their headers, for
+    /// [`Zip::extract_plan`], [`Zip::unsafe_paths`], and [`Zip::extract_all`]
+    fn extract_targets(
+        &self,
+    ) -> impl Iterator<Item = (String, Option<&CentralDirectoryFileHeader>)> {
+        let headers = self
+            .central_directory()
+            .map(|i| (i.name(self.encoding()).into_owned(), Some(i)));
+        self.implied_directories()
+            .into_iter()
+            .map(|name| (name, None))
+            .chain(headers)
+    }
+
+    /// Process the zip file stored as the item named `name`, with the same encoding
+    pub fn nested(&self, name: &str) -> Result<Zip, String> {
+        self.nested_with(name, &Zip::reader().encoding(self.encoding()))
+    }
+
+    /// Process the zip file stored as the item named `name` with the options of `reader`, except
+    /// its offset
+    ///
+    /// With [`ZipReader::max_alloc`], an item whose declared uncompressed size exceeds the limit
+    /// is refused before it is decompressed; see [`Zip::extract`].
+    pub fn nested_with(&self, name: &str, reader: &ZipReader) -> Result<Zip, String> {
+        if let (Some(header), Some(max_alloc)) = (self.find(name), reader.max_alloc) {
+            let size = u64::from(header.uncompressed_size());
+            if size > max_alloc {
+                return Err(format!(
+                    "Nested zip file `{name}`: uncompressed size ({size}) exceeds the allocation \
+                    limit of {max_alloc} bytes",
+                ));
+            }
+        }
+        let data = self.extract(name)?;
+        let reader = ZipReader {
+            offset: None,
+            ..reader.clone()
+        };
+        reader.process(&mut BufReader::new(std::io::Cursor::new(data)))
+    }
+}
@@ -1,4 +1,5 @@
 use crate::*;
+use std::fmt::Write;
 
 // Function API
 
@@ -50,13 +51,442 @@ pub fn mod_date(n: u16) -> ((u16, u8, u8), u16) {
     ((y, m, d), n)
 }
 
+/// Convert seconds since the Unix epoch into an ISO 8601 UTC date/time (`1970-01-01T00:00:00Z`)
+pub fn unix_datetime(secs: i64) -> String {
+    let (y, m, d) = civil_from_days(secs.div_euclid(86400));
+    let t = secs.rem_euclid(86400);
+    format!(
+        "{y:04}-{m:02}-{d:02}T{:02}:{:02}:{:02}Z",
+        t / 3600,
+        t % 3600 / 60,
+        t % 60,
+    )
+}
+
+/// Convert an MS-DOS date and time (see [`mod_date`] and [`mod_time`]), which are local time with
+/// no time zone, into an ISO 8601 UTC date/time (`1980-01-01T00:00:00Z`), assuming they were
+/// recorded at `offset` minutes east of UTC (see [`parse_utc_offset`])
+pub fn dos_datetime_utc(date: u16, time: u16, offset: i32) -> String {
+    let ((y, mo, d), _) = mod_date(date);
+    let ((h, mi, s), _) = mod_time(time);
+    let secs = h as i64 * 3600 + mi as i64 * 60 + s as i64;
+    let local = days_from_civil(y as i64, mo, d) * 86400 + secs;
+    unix_datetime(local - offset as i64 * 60)
+}
+
+/// Convert an NTFS FILETIME (100 ns intervals since 1601-01-01) into an ISO 8601 UTC date/time
+/// with 7 fractional digits (`1970-01-01T00:00:00.0000000Z`)
+pub fn filetime(n: u64) -> String {
+    let secs = (n / 10_000_000) as i64 - 11_644_473_600;
+    let s = unix_datetime(secs);
+    format!("{}.{:07}Z", &s[..s.len() - 1], n % 10_000_000)
+}
+
+/// Convert days since the Unix epoch into `(year, month, day)`
+///
+/// <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u8;
+    let m = (if mp < 10 { mp + 3 } else { mp - 9 }) as u8;
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    (y, m, d)
+}
+
+/// Convert `(year, month, day)` into days since the Unix epoch
+///
+/// <http://howardhinnant.github.io/date_algorithms.html#days_from_civil>
+fn days_from_civil(y: i64, m: u8, d: u8) -> i64 {
+    let (m, d) = (m as i64, d as i64);
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Decode a file name or comment as UTF-8, replacing invalid sequences with `U+FFFD`
+pub fn decode(bytes: &[u8]) -> Cow<'_, str> {
+    String::from_utf8_lossy(bytes)
+}
+
+/// Parse a size in bytes with an optional binary suffix (`K`, `M`, `G`, or `T`, optionally
+/// followed by `B` or `iB`; case insensitive), for example: `512`, `64K`, `1M`, `2GiB`
+pub fn parse_size(s: &str) -> Result<u64, String> {
+    let t = s.trim().to_uppercase();
+    let t = t
+        .strip_suffix("IB")
+        .or_else(|| t.strip_suffix('B'))
+        .unwrap_or(&t);
+    let (digits, shift) = match t.char_indices().last() {
+        Some((n, 'K')) => (&t[..n], 10),
+        Some((n, 'M')) => (&t[..n], 20),
+        Some((n, 'G')) => (&t[..n], 30),
+        Some((n, 'T')) => (&t[..n], 40),
+        _ => (t, 0),
+    };
+    digits
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(1 << shift))
+        .ok_or_else(|| format!("Invalid size: `{s}`"))
+}
+
+/// Parse a CRC-32 written in hex, with or without a `0x` prefix (case insensitive), for example:
+/// `d87f7e0c`, `0xD87F7E0C`
+pub fn parse_crc32(s: &str) -> Result<u32, String> {
+    let t = s.trim();
+    let t = t
+        .strip_prefix("0x")
+        .or_else(|| t.strip_prefix("0X"))
+        .unwrap_or(t);
+    // `from_str_radix` accepts a leading `+`
+    if t.starts_with('+') {
+        return Err(format!("Invalid CRC-32: `{s}`"));
+    }
+    u32::from_str_radix(t, 16).map_err(|_| format!("Invalid CRC-32: `{s}`"))
+}
+
+/// Parse a UTC offset as `Z`, `UTC`, or `+HH:MM`/`-HH:MM` (the colon and minutes are optional)
+/// into minutes east of UTC, for example: `Z`, `+02:00`, `-0530`, `+9`
+pub fn parse_utc_offset(s: &str) -> Result<i32, String> {
+    let t = s.trim();
+    if t.eq_ignore_ascii_case("Z") || t.eq_ignore_ascii_case("UTC") {
+        return Ok(0);
+    }
+    let err = || format!("Invalid UTC offset: `{s}`");
+    let (sign, t) = match t.chars().next() {
+        Some('+') => (1, &t[1..]),
+        Some('-') => (-1, &t[1..]),
+        _ => return Err(err()),
+    };
+    if !t.is_ascii() {
+        return Err(err());
+    }
+    let (h, m) = match t.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if t.len() > 2 => t.split_at(2),
+        None => (t, "00"),
+    };
+    let digits = |x: &str| (1..=2).contains(&x.len()) && x.bytes().all(|b| b.is_ascii_digit());
+    if !digits(h) || m.len() != 2 || !digits(m) {
+        return Err(err());
+    }
+    let (h, m) = (h.parse::<i32>().unwrap(), m.parse::<i32>().unwrap());
+    if h > 23 || m > 59 {
+        return Err(err());
+    }
+    Ok(sign * (h * 60 + m))
+}
+
+/// Replace the escape sequences `\0` (NUL), `\t`, `\n`, `\r`, and `\\` in a delimiter or
+/// separator given on the command line, for example: `\0`, `,`, `\n---\n`
+pub fn parse_escapes(s: &str) -> Result<String, String> {
+    let mut r = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            r.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('0') => r.push('\0'),
+            Some('t') => r.push('\t'),
+            Some('n') => r.push('\n'),
+            Some('r') => r.push('\r'),
+            Some('\\') => r.push('\\'),
+            Some(c) => return Err(format!("Invalid escape sequence `\\{c}` in `{s}`")),
+            None => return Err(format!("Incomplete escape sequence in `{s}`")),
+        }
+    }
+    Ok(r)
+}
+
+/// Whether an item name matches a glob pattern, for example: `*.txt`, `node_modules/**`,
+/// `**/test?.txt`
+///
+/// The pattern must match the whole name.
+/// `?` matches any character except `/`, `*` matches any run of characters except `/`, and `**`
+/// matches any run of characters including `/`; `**/` also matches no folders at all.
+/// Any other character matches itself.
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+    glob_match_chars(&pattern, &name)
+}
+
+/// Match the characters of a pattern and a name; see [`glob_match`]
+///
+/// `matches[i][j]` is whether `p[i..]` matches `n[j..]`; it is filled in from the ends
+/// backwards, so the time is proportional to the pattern length times the name length, however
+/// many `*` the pattern has.
+fn glob_match_chars(p: &[char], n: &[char]) -> bool {
+    let mut matches = vec![vec![false; n.len() + 1]; p.len() + 1];
+    matches[p.len()][n.len()] = true;
+    for i in (0..p.len()).rev() {
+        // Whether the pattern after a `**` matches the name from any position seen so far
+        let mut any = false;
+        for j in (0..=n.len()).rev() {
+            matches[i][j] = match (p[i], p.get(i + 1)) {
+                ('*', Some('*')) => {
+                    any |= matches[i + 2][j];
+                    any || (p.get(i + 2) == Some(&'/') && matches[i + 3][j])
+                }
+                ('*', _) => matches[i + 1][j] || (j < n.len() && n[j] != '/' && matches[i][j + 1]),
+                ('?', _) => j < n.len() && n[j] != '/' && matches[i + 1][j + 1],
+                (c, _) => n.get(j) == Some(&c) && matches[i + 1][j + 1],
+            };
+        }
+    }
+    matches[0][0]
+}
+
+/// Percentage of `uncompressed` bytes saved by compressing them to `compressed` bytes
+///
+/// Returns 0 if `uncompressed` is 0 (an empty zip file or only empty items), and a negative
+/// percentage if compression made the data larger.
+pub fn savings_percent(compressed: u64, uncompressed: u64) -> f64 {
+    if uncompressed == 0 {
+        0.0
+    } else {
+        (1.0 - compressed as f64 / uncompressed as f64) * 100.0
+    }
+}
+
+/// Compute the CRC-32 (ISO-HDLC; the checksum used by zip files) of `bytes`
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for b in bytes {
+        crc = CRC32_TABLE[((crc ^ *b as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+/// Lookup table for [`crc32`] (reflected polynomial `0xedb88320`)
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xedb88320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+}
+
+/// Name of a compression method from the APPNOTE
+pub fn compression_name(method: u16) -> &'static str {
+    match method {
+        0 => "stored",
+        1 => "shrunk",
+        2..=5 => "reduced",
+        6 => "imploded",
+        8 => "deflated",
+        9 => "deflate64",
+        12 => "bzip2",
+        14 => "LZMA",
+        93 => "Zstandard",
+        95 => "XZ",
+        98 => "PPMd",
+        99 => "AES",
+        _ => "unknown",
+    }
+}
+
+/// Format `bytes` as a classic hex dump (like `hexdump -C`)
+///
+/// Each line has 16 bytes: the offset of the first byte from `base` in hex, the bytes in hex in
+/// two groups of 8, and the bytes as ASCII between `|`, with `.` for unprintable bytes.
+pub fn hexdump(bytes: &[u8], base: u64) -> String {
+    let mut s = String::with_capacity((bytes.len() / 16 + 1) * 78);
+    for (n, line) in bytes.chunks(16).enumerate() {
+        write!(s, "{:08x} ", base + 16 * n as u64).unwrap();
+        for i in 0..16 {
+            if i % 8 == 0 {
+                s.push(' ');
+            }
+            match line.get(i) {
+                Some(b) => write!(s, "{b:02x} ").unwrap(),
+                None => s.push_str("   "),
+            }
+        }
+        s.push_str(" |");
+        for &b in line {
+            s.push(if b.is_ascii_graphic() || b == b' ' {
+                b as char
+            } else {
+                '.'
+            });
+        }
+        s.push_str("|\n");
+    }
+    s
+}
+
+/// Bytes that `{:?}` formats as quoted lowercase hex, the same as `hex::encode` then `{:?}` but
+/// written straight into the output, for the verbose writers
+pub(crate) struct QuotedHex<'a>(pub(crate) &'a [u8]);
+
+impl std::fmt::Debug for QuotedHex<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        const DIGITS: &[u8; 16] = b"0123456789abcdef";
+        f.write_str("\"")?;
+        let mut buf = [0; 128];
+        for chunk in self.0.chunks(64) {
+            for (n, b) in chunk.iter().enumerate() {
+                buf[2 * n] = DIGITS[(b >> 4) as usize];
+                buf[2 * n + 1] = DIGITS[(b & 0xf) as usize];
+            }
+            f.write_str(std::str::from_utf8(&buf[..2 * chunk.len()]).unwrap())?;
+        }
+        f.write_str("\"")
+    }
+}
+
+/// JSON value, serialized by [`Json::to_json`]; the one JSON writer behind [`Zip::json`],
+/// [`Zip::sbom`], and [`Zip::layout_json`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(u64),
+    String(String),
+    Array(Vec<Json>),
+
+    /// Members in order; duplicate keys are written as is
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Object with `members` in order
+    pub fn object<'a>(members: impl IntoIterator<Item = (&'a str, Json)>) -> Self {
+        Json::Object(
+            members
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+        )
+    }
+
+    /// Serialize the value
+    ///
+    /// If `pretty` is true, each array element and object member is on its own line, indented
+    /// with 2 spaces per level; otherwise the value is a single line.
+    /// Empty arrays and objects are `[]` and `{}`.
+    pub fn to_json(&self, pretty: bool) -> String {
+        let mut s = String::new();
+        self.write(&mut s, pretty, 0);
+        s
+    }
+
+    /// Append the value to `s` at nesting level `level`
+    fn write(&self, s: &mut String, pretty: bool, level: usize) {
+        let members = match self {
+            Json::Null => return s.push_str("null"),
+            Json::Bool(b) => return s.push_str(&b.to_string()),
+            Json::Number(n) => return s.push_str(&n.to_string()),
+            Json::String(x) => return s.push_str(&json_string(x)),
+            Json::Array(items) => items.iter().map(|v| (None, v)).collect::<Vec<_>>(),
+            Json::Object(members) => members.iter().map(|(k, v)| (Some(k.as_str()), v)).collect(),
+        };
+        let (open, close) = match self {
+            Json::Array(_) => ('[', ']'),
+            _ => ('{', '}'),
+        };
+        s.push(open);
+        for (n, (key, value)) in members.iter().enumerate() {
+            if n > 0 {
+                s.push(',');
+            }
+            if pretty {
+                s.push('\n');
+                s.push_str(&"  ".repeat(level + 1));
+            }
+            if let Some(key) = key {
+                s.push_str(&json_string(key));
+                s.push_str(if pretty { ": " } else { ":" });
+            }
+            value.write(s, pretty, level + 1);
+        }
+        if pretty && !members.is_empty() {
+            s.push('\n');
+            s.push_str(&"  ".repeat(level));
+        }
+        s.push(close);
+    }
+}
+
+/// Quote and escape a string as a JSON string literal
+pub fn json_string(s: &str) -> String {
+    let mut r = String::with_capacity(s.len() + 2);
+    r.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => r.push_str("\\\""),
+            '\\' => r.push_str("\\\\"),
+            '\n' => r.push_str("\\n"),
+            '\r' => r.push_str("\\r"),
+            '\t' => r.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(r, "\\u{:04x}", c as u32).unwrap(),
+            c => r.push(c),
+        }
+    }
+    r.push('"');
+    r
+}
+
 /// Convert a `binrw::Error::BadMagic.found` (`[0, 1, 2, 3]`) into a nice hex string (`00010203`)
+///
+/// Anything that isn't a list of bytes is returned unchanged, so no part of it is lost.
 pub fn magic_hex(magic: &str) -> String {
-    magic
-        .replace("[", "")
-        .replace("]", "")
-        .split(", ")
-        .map(|x| format!("{:02x}", x.parse::<u8>().unwrap()))
-        .collect::<Vec<String>>()
-        .join("")
+    let bytes = magic
+        .strip_prefix('[')
+        .and_then(|x| x.strip_suffix(']'))
+        .and_then(|x| {
+            x.split(", ")
+                .map(|x| x.parse::<u8>().ok())
+                .collect::<Option<Vec<u8>>>()
+        });
+    match bytes {
+        Some(bytes) => hex::encode(bytes),
+        None => magic.to_string(),
+    }
+}
+
+/// Identify a common non-zip file format (gzip, tar, 7z, or RAR) from the first bytes of a file
+///
+/// Tar files are recognized by the `ustar` magic at offset 257, so pass at least 262 bytes.
+/// Bytes that start with a zip signature (`PK`) are never identified as another format.
+pub fn sniff_format(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"PK") {
+        None
+    } else if bytes.starts_with(b"\x1f\x8b") {
+        Some("gzip")
+    } else if bytes.starts_with(b"7z\xbc\xaf\x27\x1c") {
+        Some("7z")
+    } else if bytes.starts_with(b"Rar!\x1a\x07") {
+        Some("RAR")
+    } else if bytes.get(257..262) == Some(b"ustar") {
+        Some("tar")
+    } else {
+        None
+    }
 }
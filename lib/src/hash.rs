@@ -0,0 +1,95 @@
+use crate::*;
+use sha2::{Digest, Sha256};
+
+/// SHA-256 of `bytes` in lowercase hex
+pub fn sha256(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Process the raw bytes of a zip file like [`process`] and compute the SHA-256 of the bytes in
+/// the same pass; see [`ZipReader::hash`]
+///
+/// Returns the output and the digest in lowercase hex.
+pub fn process_and_hash<R>(r: &mut BufReader<R>, verbose: bool) -> Result<(String, String), String>
+where
+    R: Read + Seek,
+{
+    let mut zip = Zip::reader().hash(true).process(r)?;
+    let digest = zip.sha256.take().unwrap_or_default();
+    Ok((zip.output(verbose)?, digest))
+}
+
+/// Reader that hashes the bytes it passes through
+///
+/// Bytes are hashed in file order as they are first read from the starting position onward;
+/// bytes skipped by a seek are hashed by [`HashReader::finish`].
+struct HashReader<R> {
+    inner: R,
+    hasher: Sha256,
+    pos: u64,
+    hashed: u64,
+}
+
+impl<R: Read + Seek> HashReader<R> {
+    fn new(mut inner: R) -> std::io::Result<Self> {
+        let pos = inner.stream_position()?;
+        Ok(HashReader {
+            inner,
+            hasher: Sha256::new(),
+            pos,
+            hashed: pos,
+        })
+    }
+
+    /// Hash any bytes not read yet up to the end of the file and return the digest in hex
+    fn finish(mut self) -> std::io::Result<String> {
+        self.seek(SeekFrom::Start(self.hashed))?;
+        let mut buf = [0; 8192];
+        while self.read(&mut buf)? > 0 {}
+        Ok(hex::encode(self.hasher.finalize()))
+    }
+}
+
+impl<R: Read> Read for HashReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        let end = self.pos + n as u64;
+        if self.pos <= self.hashed && end > self.hashed {
+            self.hasher
+                .update(&buf[(self.hashed - self.pos) as usize..n]);
+            self.hashed = end;
+        }
+        self.pos = end;
+        Ok(n)
+    }
+}
+
+impl<R: Seek> Seek for HashReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.pos = self.inner.seek(pos)?;
+        Ok(self.pos)
+    }
+}
+
+impl ZipReader {
+    /// Compute the SHA-256 of the bytes from the start offset (or the reader's current position)
+    /// to the end of the file while parsing them; see [`Zip::sha256`]
+    ///
+    /// The bytes are hashed as they are parsed, so fingerprinting a large zip file does not take
+    /// a second full read; only bytes that parsing skips (such as trailing data) are read again.
+    pub fn hash(mut self, hash: bool) -> Self {
+        self.hash = hash;
+        self
+    }
+
+    /// Parse with the options while hashing the bytes, for [`ZipReader::process`]
+    pub(crate) fn parse_hashed<R>(&self, r: &mut BufReader<R>) -> Result<Zip, String>
+    where
+        R: Read + Seek,
+    {
+        let mut hr = HashReader::new(r).map_err(|e| e.to_string())?;
+        let mut zip = self.parse(&mut BufReader::new(&mut hr))?;
+        zip.sha256 = Some(hr.finish().map_err(|e| e.to_string())?);
+        Ok(zip)
+    }
+}
@@ -0,0 +1,137 @@
+use crate::*;
+
+/// Overview of a zip file; see [`Zip::info`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ZipInfo {
+    /// Whether the zip file uses Zip64 fields; see [`Zip::is_zip64`]
+    pub zip64: bool,
+
+    /// Number of central directory file headers
+    pub entries: usize,
+
+    /// File and folder counts, total sizes, and encryption
+    pub stats: ZipStats,
+
+    /// Compression methods used, in ascending order
+    pub compression_methods: Vec<u16>,
+
+    /// Highest version needed to extract; see [`Zip::required_version`]
+    pub required_version: u16,
+
+    /// Whether the zip file spans multiple disks; see [`Zip::is_multi_disk`]
+    pub multi_disk: bool,
+
+    /// Whether the zip file looks streamed; see [`Zip::is_streamed`]
+    pub streamed: bool,
+
+    /// Zip file comment, if any; see [`Zip::comment`]
+    pub comment: Option<String>,
+}
+
+impl std::fmt::Display for ZipInfo {
+    /// Multi-line `field: value` report
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let yes_no = |b: bool| if b { "yes" } else { "no" };
+        let methods = self
+            .compression_methods
+            .iter()
+            .map(|&i| format!("{i} ({})", compression_name(i)))
+            .collect::<Vec<_>>();
+        writeln!(f, "format: {}", if self.zip64 { "zip64" } else { "zip" })?;
+        writeln!(
+            f,
+            "entries: {} ({} files, {} dirs)",
+            self.entries, self.stats.files, self.stats.dirs,
+        )?;
+        writeln!(
+            f,
+            "total size: {} (compressed {}, {:.1}% saved)",
+            self.stats.uncompressed_size,
+            self.stats.compressed_size,
+            savings_percent(self.stats.compressed_size, self.stats.uncompressed_size),
+        )?;
+        writeln!(
+            f,
+            "compression methods: {}",
+            if methods.is_empty() {
+                String::from("none")
+            } else {
+                methods.join(", ")
+            },
+        )?;
+        writeln!(f, "encrypted: {}", yes_no(self.stats.encrypted > 0))?;
+        writeln!(
+            f,
+            "required version: {}.{}",
+            self.required_version / 10,
+            self.required_version % 10,
+        )?;
+        writeln!(f, "multi-disk: {}", yes_no(self.multi_disk))?;
+        writeln!(f, "streamed: {}", yes_no(self.streamed))?;
+        write!(
+            f,
+            "comment: {}",
+            self.comment.as_deref().unwrap_or("(none)"),
+        )
+    }
+}
+
+impl Zip {
+    /// Summarize the zip file as a whole, without listing its items
+    pub fn info(&self) -> ZipInfo {
+        ZipInfo {
+            zip64: self.is_zip64(),
+            entries: self.central_directory().count(),
+            stats: self.stats(),
+            compression_methods: self.compression_methods(),
+            required_version: self.required_version(),
+            multi_disk: self.is_multi_disk(),
+            streamed: self.is_streamed(),
+            comment: self.comment().map(|i| i.into_owned()),
+        }
+    }
+
+    /// Distinct compression methods of the items, in ascending order
+    pub fn compression_methods(&self) -> Vec<u16> {
+        let mut methods = self
+            .central_directory()
+            .map(|i| i.compression())
+            .collect::<Vec<_>>();
+        methods.sort_unstable();
+        methods.dedup();
+        methods
+    }
+
+    /// Highest version needed to extract any item (major version * 10 + minor version)
+    pub fn required_version(&self) -> u16 {
+        self.central_directory()
+            .map(|i| i.version_needed())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Whether the end of central directory record refers to a disk other than the first
+    pub fn is_multi_disk(&self) -> bool {
+        self.end_of_central_directory()
+            .map_or(false, |i| i.disk_number() != 0 || i.disk_number_w_cd() != 0)
+    }
+
+    /// Whether the zip file uses Zip64 fields
+    ///
+    /// True if an end of central directory record field is saturated (`0xffff` or
+    /// `0xffffffff`), or if a central directory file header has a Zip64 extended information
+    /// extra field (`0x0001`).
+    pub fn is_zip64(&self) -> bool {
+        let eocd = self.end_of_central_directory().map_or(false, |i| {
+            i.disk_entries() == u16::MAX
+                || i.total_entries() == u16::MAX
+                || i.cd_size() == u32::MAX
+                || i.cd_offset() == u32::MAX
+        });
+        eocd || self.central_directory().any(|i| {
+            i.extra_fields().map_or(false, |fields| {
+                fields.iter().any(|field| field.tag == 0x0001)
+            })
+        })
+    }
+}
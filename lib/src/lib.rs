@@ -5,6 +5,7 @@
 //! enums:
 //!
 //! * [`LocalFile`]: Local file header, file data, and data descriptor
+//! * [`ArchiveExtraDataRecord`]: Archive extra data record
 //! * [`CentralDirectoryFileHeader`]: Central directory file header
 //! * [`EndOfCentralDirectoryRecord`]: End of central directory record
 //!
@@ -49,6 +50,21 @@
 //! );
 //! ```
 //!
+//! Use [`Zip::reader`] to combine options such as the encoding, strict mode, lenient parsing,
+//! and a starting offset in a single call instead of chaining separate constructors:
+//!
+//! ```
+//! use zp_lib::{Encoding, Zip};
+//!
+//! let zip = Zip::reader()
+//!     .encoding(Encoding::Utf8)
+//!     .strict(true)
+//!     .open("../exercise.zip")
+//!     .unwrap();
+//!
+//! assert_eq!(zip.stats().files, 10);
+//! ```
+//!
 //! # Function API
 //!
 //! Also, the [`process_file`] and [`process`] functions enable reading zip file data from a file
@@ -76,17 +92,57 @@
 //! );
 //! ```
 
-use binrw::{io::{Read, Seek}, prelude::*, until_eof, BinReaderExt, Error};
+use binrw::{io::{Read, Seek, SeekFrom}, prelude::*, until_eof, BinReaderExt, Error, ReadOptions};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::BufReader;
 use std::fs::File;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+mod audit;
+mod check;
+mod encoding;
+mod encryption;
 mod entries;
+mod extra;
+mod extract;
 mod functions;
+#[cfg(feature = "hash")]
+mod hash;
+mod info;
+mod locate;
+mod manifest;
+mod multi;
+mod probe;
+mod reader;
+mod sbom;
+mod strict;
+mod tree;
+mod verify;
+mod warning;
 mod zip;
 
+pub use audit::*;
+pub use check::*;
+pub use encoding::*;
+pub use encryption::*;
 pub use entries::*;
+pub use extra::*;
+pub use extract::*;
 pub use functions::*;
+#[cfg(feature = "hash")]
+pub use hash::*;
+pub use info::*;
+pub use locate::*;
+pub use manifest::*;
+pub use multi::*;
+pub use probe::*;
+pub use reader::*;
+pub use sbom::*;
+pub use strict::*;
+pub use tree::*;
+pub use verify::*;
+pub use warning::*;
 pub use zip::*;
 
 #[cfg(test)]
@@ -98,6 +154,195 @@ mod tests {
 
     const VERBOSE: &str = include_str!("../../exercise.zip-process-verbose.txt");
     const SUMMARY: &str = include_str!("../../exercise.zip-process-summary.txt");
+    const PRETTY: &str = include_str!("../../exercise.zip-process-pretty.txt");
+
+    // Hand-built zip files
+
+    /// Fields of a hand-built zip file item
+    #[derive(Clone, Copy, Default)]
+    struct Item<'a> {
+        name: &'a str,
+        flags: u16,
+        compression: u16,
+        crc32: u32,
+        data: &'a [u8],
+        extra: &'a [u8],
+        comment: &'a str,
+
+        /// Write zeros for the CRC-32 and sizes in the local file header, as a streaming writer
+        /// does
+        streamed: bool,
+
+        /// Data descriptor (CRC-32, compressed size, uncompressed size) after the file data
+        descriptor: Option<[u32; 3]>,
+
+        /// Leave out the data descriptor signature
+        unsigned_descriptor: bool,
+    }
+
+    impl Item<'_> {
+        /// Local file header, file data, and data descriptor
+        fn local(&self) -> Vec<u8> {
+            let mut v = b"PK\x03\x04".to_vec();
+            for n in [20, self.flags, self.compression, 0, 0x21] {
+                v.extend(n.to_le_bytes());
+            }
+            let [crc32, size] = if self.streamed {
+                [0, 0]
+            } else {
+                [self.crc32, self.data.len() as u32]
+            };
+            for n in [crc32, size, size] {
+                v.extend(n.to_le_bytes());
+            }
+            for n in [self.name.len() as u16, self.extra.len() as u16] {
+                v.extend(n.to_le_bytes());
+            }
+            v.extend(self.name.as_bytes());
+            v.extend(self.extra);
+            v.extend(self.data);
+            if let Some(descriptor) = self.descriptor {
+                if !self.unsigned_descriptor {
+                    v.extend(b"PK\x07\x08");
+                }
+                for n in descriptor {
+                    v.extend(n.to_le_bytes());
+                }
+            }
+            v
+        }
+
+        /// Central directory file header pointing to a local file header at `lfh_offset`
+        fn central(&self, lfh_offset: u32) -> Vec<u8> {
+            let mut v = b"PK\x01\x02".to_vec();
+            for n in [20, 20, self.flags, self.compression, 0, 0x21] {
+                v.extend(n.to_le_bytes());
+            }
+            for n in [self.crc32, self.data.len() as u32, self.data.len() as u32] {
+                v.extend(n.to_le_bytes());
+            }
+            for n in [
+                self.name.len() as u16,
+                self.extra.len() as u16,
+                self.comment.len() as u16,
+                0,
+                0,
+            ] {
+                v.extend(n.to_le_bytes());
+            }
+            for n in [0, lfh_offset] {
+                v.extend(n.to_le_bytes());
+            }
+            v.extend(self.name.as_bytes());
+            v.extend(self.extra);
+            v.extend(self.comment.as_bytes());
+            v
+        }
+    }
+
+    /// Layout of a hand-built zip file; see [`build_zip`]
+    #[derive(Default)]
+    struct Layout<'a> {
+        /// Bytes before the first local file header, e.g. a self-extracting stub
+        prefix: &'a [u8],
+
+        /// Whether the offsets include the prefix, as after `zip -A`; otherwise they are relative
+        /// to the first local file header, as when a stub is simply prepended
+        adjusted: bool,
+
+        /// Items, each written as a local file
+        items: &'a [Item<'a>],
+
+        /// Bytes between the local files and the central directory, e.g. an archive extra data
+        /// record or damage
+        between: &'a [u8],
+
+        /// Central directory file headers and their `lfh_offset`s, instead of one header per item
+        /// pointing to its local file header, e.g. for a local/central mismatch
+        central: Option<&'a [(Item<'a>, u32)]>,
+
+        /// End of central directory record entry count instead of the number of headers
+        entries: Option<u16>,
+
+        /// End of central directory record central directory size instead of the actual size
+        cd_size: Option<u32>,
+
+        /// End of central directory record central directory offset instead of the actual offset
+        cd_offset: Option<u32>,
+
+        /// Leave out the end of central directory record
+        omit_eocd: bool,
+    }
+
+    impl<'a> From<&'a [Item<'a>]> for Layout<'a> {
+        fn from(items: &'a [Item<'a>]) -> Self {
+            Layout {
+                items,
+                ..Default::default()
+            }
+        }
+    }
+
+    impl<'a, const N: usize> From<&'a [Item<'a>; N]> for Layout<'a> {
+        fn from(items: &'a [Item<'a>; N]) -> Self {
+            Layout {
+                items,
+                ..Default::default()
+            }
+        }
+    }
+
+    /// Prefix, local files, central directory, and end of central directory record of a layout
+    /// (or just `items`)
+    fn build_zip<'a>(layout: impl Into<Layout<'a>>) -> Vec<u8> {
+        let layout = layout.into();
+        let mut v = layout.prefix.to_vec();
+        let base = if layout.adjusted { 0 } else { v.len() as u32 };
+        let mut offsets = vec![];
+        for i in layout.items {
+            offsets.push(v.len() as u32 - base);
+            v.extend(i.local());
+        }
+        v.extend(layout.between);
+        let cd_offset = v.len() as u32 - base;
+        let mut entries = 0;
+        match layout.central {
+            Some(headers) => {
+                for (i, offset) in headers {
+                    v.extend(i.central(*offset));
+                    entries += 1;
+                }
+            }
+            None => {
+                for (i, offset) in layout.items.iter().zip(offsets) {
+                    v.extend(i.central(offset));
+                    entries += 1;
+                }
+            }
+        }
+        let cd_size = v.len() as u32 - base - cd_offset;
+        if !layout.omit_eocd {
+            v.extend(b"PK\x05\x06");
+            let entries = layout.entries.unwrap_or(entries);
+            for n in [0, 0, entries, entries] {
+                v.extend(n.to_le_bytes());
+            }
+            for n in [
+                layout.cd_size.unwrap_or(cd_size),
+                layout.cd_offset.unwrap_or(cd_offset),
+            ] {
+                v.extend(n.to_le_bytes());
+            }
+            v.extend(0u16.to_le_bytes());
+        }
+        v
+    }
+
+    /// Process hand-built zip file bytes
+    fn process_bytes(bytes: Vec<u8>) -> Result<Zip, String> {
+        let mut r = BufReader::new(Cursor::new(bytes));
+        Zip::process(&mut r)
+    }
 
     // Process functions
 
@@ -137,6 +382,35 @@ mod tests {
         assert_eq!(process(&mut r, false).unwrap(), SUMMARY);
     }
 
+    #[cfg(feature = "hash")]
+    #[test]
+    fn zip_reader_hash_test() {
+        let digest = "ba390440cc0b930294c2d25314392dcfad4d756d80a3f76e724e743b75f37b4e";
+        let zip = Zip::reader().hash(true).open("../exercise.zip").unwrap();
+        assert_eq!(zip.summary().unwrap(), SUMMARY);
+        assert_eq!(zip.sha256(), Some(digest));
+        assert_eq!(Zip::from("../exercise.zip").unwrap().sha256(), None);
+        let mut r = BufReader::new(File::open("../exercise.zip").unwrap());
+        assert_eq!(
+            process_and_hash(&mut r, false).unwrap(),
+            (String::from(SUMMARY), String::from(digest)),
+        );
+
+        // Trailing data is hashed too, and hashing combines with the other options
+        let mut bytes = std::fs::read("../exercise.zip").unwrap();
+        bytes.extend(b"trailing");
+        let mut r = BufReader::new(Cursor::new(bytes.clone()));
+        let zip = Zip::reader().hash(true).process(&mut r).unwrap();
+        assert_eq!(zip.trailing_bytes(), 8);
+        assert_eq!(zip.sha256(), Some(sha256(&bytes).as_str()));
+        let zip = Zip::reader()
+            .hash(true)
+            .strict(true)
+            .open("../exercise.zip")
+            .unwrap();
+        assert_eq!(zip.sha256(), Some(digest));
+    }
+
     #[test]
     fn process_file_nonexistent_test() {
         assert_eq!(
@@ -170,6 +444,7 @@ mod tests {
         let zip = Zip::from("../exercise.zip").unwrap();
         assert_eq!(zip.summary().unwrap(), SUMMARY);
         assert_eq!(zip.verbose().unwrap(), VERBOSE);
+        assert_eq!(zip.pretty().unwrap(), PRETTY);
     }
 
     #[test]
@@ -198,24 +473,2871 @@ mod tests {
     }
 
     #[test]
-    fn zip_process_eof_test() {
-        let bytes = hex::decode("00").unwrap();
-        let cursor = Cursor::new(&bytes);
-        let mut reader = BufReader::new(cursor);
+    fn zip_stats_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let stats = zip.stats();
         assert_eq!(
-            Zip::process(&mut reader).unwrap_err(),
-            String::from("Unexpected end of file"),
+            stats,
+            ZipStats {
+                files: 10,
+                dirs: 3,
+                compressed_size: 1293,
+                uncompressed_size: 2639,
+                encrypted: 0,
+            },
+        );
+        assert_eq!(
+            stats.to_string(),
+            "10 files, 3 dirs, total 2639, not encrypted",
         );
     }
 
     #[test]
-    fn zip_process_invalid_sig_test() {
-        let bytes = hex::decode("00000001").unwrap();
-        let cursor = Cursor::new(&bytes);
-        let mut reader = BufReader::new(cursor);
+    fn zip_counts_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let counts = zip.counts();
         assert_eq!(
-            Zip::process(&mut reader).unwrap_err(),
-            String::from("Invalid signature: `00000001`"),
+            counts,
+            RecordCounts {
+                local_files: 13,
+                archive_extra_data_records: 0,
+                central_directory_file_headers: 13,
+                end_of_central_directory_records: 1,
+            },
+        );
+        assert_eq!(counts.to_string(), "local 13, central 13, eocd 1, consistent");
+
+        // Local file without a central directory
+        let item = Item {
+            name: "a.txt",
+            data: b"test",
+            ..Default::default()
+        };
+        let zip = process_bytes(build_zip(Layout {
+            items: &[item],
+            central: Some(&[]),
+            omit_eocd: true,
+            ..Default::default()
+        }))
+        .unwrap();
+        assert!(!zip.counts().is_consistent());
+        assert_eq!(
+            zip.counts().to_string(),
+            "local 1, central 0, eocd 0, inconsistent",
+        );
+    }
+
+    #[test]
+    fn zip_is_streamed_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        assert!(!zip.is_streamed());
+
+        // Central directory offset that does not match the local files
+        let item = Item {
+            name: "a.txt",
+            data: b"test",
+            ..Default::default()
+        };
+        let zip = process_bytes(build_zip(Layout {
+            items: &[item],
+            cd_offset: Some(40),
+            ..Default::default()
+        }))
+        .unwrap();
+        assert!(!zip.local_file_at(0).unwrap().is_streamed());
+        assert!(zip.is_streamed());
+
+        // Local file header with zero sizes and a data descriptor
+        let item = Item {
+            name: "a.txt",
+            flags: 1 << 3,
+            descriptor: Some([0, 0, 0]),
+            ..Default::default()
+        };
+        let zip = process_bytes(build_zip(&[item])).unwrap();
+        assert!(zip.local_file_at(0).unwrap().is_streamed());
+
+        assert!(zip.is_streamed());
+    }
+
+    #[test]
+    fn zip_info_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let info = zip.info();
+        assert_eq!(info.compression_methods, vec![0, 8]);
+        assert_eq!(info.required_version, 20);
+        assert!(!info.zip64 && !info.multi_disk && !info.streamed);
+        assert_eq!(
+            info.to_string(),
+            "\
+format: zip
+entries: 13 (10 files, 3 dirs)
+total size: 2639 (compressed 1293, 51.0% saved)
+compression methods: 0 (stored), 8 (deflated)
+encrypted: no
+required version: 2.0
+multi-disk: no
+streamed: no
+comment: (none)",
+        );
+    }
+
+    #[test]
+    fn zip_total_savings_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let (compressed, uncompressed, percent) = zip.total_savings();
+        assert_eq!((compressed, uncompressed), (1293, 2639));
+        assert_eq!(format!("{percent:.1}"), "51.0");
+        let zip = process_bytes(build_zip(Layout::default())).unwrap();
+        assert_eq!(zip.total_savings(), (0, 0, 0.0));
+    }
+
+    #[test]
+    fn zip_summary_size_range_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let filter = |min: u64, max: u64| {
+            SUMMARY
+                .lines()
+                .filter(|x| {
+                    let size = x.split('\t').nth(2).unwrap().parse::<u64>().unwrap();
+                    size >= min && size <= max
+                })
+                .map(|x| format!("{x}\n"))
+                .collect::<String>()
+        };
+        let big = zip.summary_size_range(Some(100), None).unwrap();
+        assert_eq!(big.lines().count(), 3);
+        assert_eq!(big, filter(100, u64::MAX));
+        let small = zip.summary_size_range(Some(1), Some(4)).unwrap();
+        assert_eq!(small.lines().count(), 5);
+        assert_eq!(small, filter(1, 4));
+        assert_eq!(zip.summary_size_range(None, None).unwrap(), SUMMARY);
+    }
+
+    #[test]
+    fn zip_summary_excluding_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let filter = |f: &dyn Fn(&str) -> bool| {
+            SUMMARY
+                .lines()
+                .filter(|x| f(x.split('\t').next().unwrap()))
+                .map(|x| format!("{x}\n"))
+                .collect::<String>()
+        };
+        assert_eq!(
+            zip.summary_excluding(&["folder00/**"]).unwrap(),
+            filter(&|name| !name.starts_with("folder00/")),
+        );
+        assert_eq!(zip.summary_excluding(&[]).unwrap(), SUMMARY);
+
+        // Exclude takes precedence over filter
+        let options = SummaryOptions {
+            filter: vec![String::from("**/*.txt")],
+            exclude: vec![String::from("folder00/**"), String::from("test02.txt")],
+            ..Default::default()
+        };
+        assert_eq!(
+            zip.summary_with(&options).unwrap(),
+            filter(&|name| {
+                name.ends_with(".txt") && !name.starts_with("folder00/") && name != "test02.txt"
+            }),
+        );
+    }
+
+    #[test]
+    fn zip_summary_delimiter_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let mut options = SummaryOptions {
+            delimiter: Some(String::from("\0")),
+            separator: Some(String::from("\0")),
+            ..Default::default()
+        };
+        let want = SUMMARY
+            .lines()
+            .map(|x| format!("{}\0", x.replace('\t', "\0")))
+            .collect::<String>();
+        assert_eq!(zip.summary_with(&options).unwrap(), want);
+
+        options.verify = true;
+        let summary = zip.summary_with(&options).unwrap();
+        let first = summary.split('\0').take(6).collect::<Vec<_>>();
+        assert_eq!(
+            first,
+            ["folder00/", "true", "0", "2022-05-19T10:51:38", "", "OK"]
+        );
+    }
+
+    #[test]
+    fn zip_verbose_with_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        assert_eq!(zip.verbose_with("---\n").unwrap(), VERBOSE);
+        assert_eq!(
+            zip.verbose_with("\0").unwrap(),
+            VERBOSE.replace("---\n", "\0"),
+        );
+        assert_eq!(
+            zip.verbose_without_eof("---\n").unwrap(),
+            VERBOSE.strip_suffix("---\nEOF\n---\n").unwrap(),
+        );
+    }
+
+    #[test]
+    fn zip_comment_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        assert_eq!(zip.comment(), None);
+        let options = SummaryOptions {
+            comment: true,
+            ..Default::default()
+        };
+        assert_eq!(zip.summary_with(&options).unwrap(), SUMMARY);
+
+        let item = Item {
+            name: "a.txt",
+            data: b"test",
+            ..Default::default()
+        };
+        let mut bytes = build_zip(&[item]);
+        let comment = b"build 42\nrelease";
+        let len = bytes.len();
+        bytes[len - 2..].copy_from_slice(&(comment.len() as u16).to_le_bytes());
+        bytes.extend(comment);
+        let zip = process_bytes(bytes).unwrap();
+        assert_eq!(zip.comment().unwrap(), "build 42\nrelease");
+        assert_eq!(
+            zip.summary_with(&options).unwrap(),
+            "\
+# build 42
+# release
+a.txt\tfalse\t4\t1980-01-01T00:00:00\t
+",
+        );
+    }
+
+    #[test]
+    fn zip_find_by_crc_size_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let names = |v: Vec<&CentralDirectoryFileHeader>| {
+            v.iter()
+                .map(|i| i.name(Encoding::Auto).into_owned())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(zip.find_by_crc(0xa2fb7922)),
+            vec!["folder00/folder00-00/test00-00-01.txt"],
+        );
+        assert_eq!(names(zip.find_by_size(95)), vec!["folder00/test00-00.txt"]);
+        assert_eq!(zip.find_by_crc(0xd87f7e0c).len(), 5);
+        assert_eq!(zip.find_by_size(4).len(), 5);
+        assert!(zip.find_by_size(1).is_empty());
+        let options = SummaryOptions {
+            crc32: Some(0xd87f7e0c),
+            size: Some(4),
+            ..Default::default()
+        };
+        assert_eq!(zip.summary_with(&options).unwrap().lines().count(), 5);
+    }
+
+    #[test]
+    fn zip_summary_tree_order_test() {
+        let items = [
+            Item {
+                name: "b/",
+                ..Default::default()
+            },
+            Item {
+                name: "a-b/x",
+                ..Default::default()
+            },
+            Item {
+                name: "a/x",
+                ..Default::default()
+            },
+            Item {
+                name: "b/y",
+                ..Default::default()
+            },
+            Item {
+                name: "a/",
+                ..Default::default()
+            },
+            Item {
+                name: "c",
+                ..Default::default()
+            },
+        ];
+        let zip = process_bytes(build_zip(&items)).unwrap();
+        let options = SummaryOptions {
+            tree_order: true,
+            ..Default::default()
+        };
+        let names = zip
+            .summary_with(&options)
+            .unwrap()
+            .lines()
+            .map(|x| x.split('\t').next().unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["a/", "a/x", "a-b/x", "b/", "b/y", "c"]);
+
+        // exercise.zip is already in tree order
+        let zip = Zip::from("../exercise.zip").unwrap();
+        assert_eq!(zip.summary_with(&options).unwrap(), SUMMARY);
+    }
+
+    #[test]
+    fn zip_summary_detailed_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let options = SummaryOptions {
+            detailed: true,
+            filter: vec![String::from("folder00/*.txt")],
+            ..Default::default()
+        };
+        assert_eq!(
+            zip.summary_with(&options).unwrap(),
+            "\
+folder00/test00-00.txt\tfalse\t95\t2022-05-19T10:57:24\t\tdeflated\t91\t95
+folder00/test00-01.txt\tfalse\t0\t2021-08-25T13:04:38\tThis file doesn't have any content\tstored\t0\t0
+",
+        );
+
+        // The local file header of `a.txt` disagrees on the compression method, and `b.txt` has
+        // none
+        let local = Item {
+            name: "a.txt",
+            compression: 8,
+            data: b"test",
+            ..Default::default()
+        };
+        let central = [
+            (
+                Item {
+                    compression: 0,
+                    ..local
+                },
+                0,
+            ),
+            (
+                Item {
+                    name: "b.txt",
+                    ..Default::default()
+                },
+                1,
+            ),
+        ];
+        let zip = process_bytes(build_zip(Layout {
+            items: &[local],
+            central: Some(&central),
+            ..Default::default()
+        }))
+        .unwrap();
+        let options = SummaryOptions {
+            method: true,
+            detailed: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            zip.summary_with(&options).unwrap(),
+            "\
+a.txt\tfalse\t4\t1980-01-01T00:00:00\t\tstored\tdeflated\t4\t4
+b.txt\tfalse\t0\t1980-01-01T00:00:00\t\tstored\t-\t-\t-
+",
+        );
+    }
+
+    #[test]
+    fn zip_names_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let names = zip.names();
+        assert_eq!(names.len(), 13);
+        assert_eq!(names[0], "folder00/");
+        assert_eq!(names[8], "folder01/exercise.zip");
+        assert_eq!(names[12], "test02.txt");
+    }
+
+    #[test]
+    fn zip_manifest_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let manifest = zip.manifest();
+        let lines = manifest.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 14);
+        assert_eq!(
+            lines[0],
+            "name\toffset\tcompressed_size\tuncompressed_size\tcrc32\tmethod",
+        );
+        assert_eq!(
+            lines[4],
+            "folder00/folder00-00/test00-00-01.txt\t161\t113\t125\t2734389538\t8",
+        );
+        assert_eq!(lines[13], "test02.txt\t1906\t4\t4\t3632233996\t0");
+    }
+
+    #[test]
+    fn zip_matches_manifest_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let expected = parse_manifest(&zip.manifest()).unwrap();
+        assert_eq!(expected.len(), 13);
+        assert_eq!(expected[12], (String::from("test02.txt"), 3632233996, 4));
+        assert_eq!(zip.matches_manifest(&expected), vec![]);
+
+        let expected = parse_manifest(
+            "\
+uncompressed_size\tname\tcrc32
+
+4\ttest00.txt\t3632233996
+5\ttest01.txt\t3632233996
+0\tmissing.txt\t0
+",
+        )
+        .unwrap();
+        let mismatches = zip.matches_manifest(&expected);
+        assert_eq!(mismatches.len(), 2 + 11);
+        assert_eq!(
+            mismatches[0],
+            Mismatch::Differs {
+                name: String::from("test01.txt"),
+                expected_crc32: 0xd87f7e0c,
+                crc32: 0xd87f7e0c,
+                expected_size: 5,
+                size: 4,
+            },
+        );
+        assert_eq!(
+            mismatches[0].to_string(),
+            "Differs `test01.txt`: CRC-32 0xd87f7e0c and size 4, expected 0xd87f7e0c and 5",
+        );
+        assert_eq!(mismatches[1].to_string(), "Missing `missing.txt`");
+        assert_eq!(mismatches[2].to_string(), "Extra `folder00/`");
+        assert_eq!(mismatches[12].to_string(), "Extra `test02.txt`");
+
+        assert_eq!(
+            parse_manifest("name\tcrc32\n").unwrap_err(),
+            "Manifest header must have the `name`, `crc32`, and `uncompressed_size` columns",
+        );
+        assert_eq!(
+            parse_manifest("name\tcrc32\tuncompressed_size\na.txt\tx\t4\n").unwrap_err(),
+            "Invalid manifest line 2: `a.txt\tx\t4`",
+        );
+    }
+
+    #[cfg(all(feature = "deflate", feature = "hash"))]
+    #[test]
+    fn zip_sbom_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let sbom = zip.sbom(false);
+        assert!(sbom.starts_with(r#"{"format":"zp-sbom","version":1,"files":[{"name":"#));
+        assert_eq!(sbom.matches(r#""name":"#).count(), 10);
+        assert!(sbom.contains(
+            r#"{"name":"test00.txt","size":4,"crc32":3632233996,"sha256":"9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"}"#
+        ));
+
+        // Encrypted items have no SHA-256
+        let item = Item {
+            name: "a.txt",
+            flags: 1,
+            crc32: 0xd87f7e0c,
+            data: b"test",
+            ..Default::default()
+        };
+        let zip = process_bytes(build_zip(&[item])).unwrap();
+        assert_eq!(
+            zip.sbom(true),
+            r#"{
+  "format": "zp-sbom",
+  "version": 1,
+  "files": [
+    {
+      "name": "a.txt",
+      "size": 4,
+      "crc32": 3632233996,
+      "sha256": null
+    }
+  ]
+}"#,
+        );
+        let zip = process_bytes(build_zip(Layout::default())).unwrap();
+
+        assert_eq!(zip.sbom(false), r#"{"format":"zp-sbom","version":1,"files":[]}"#);
+    }
+
+    #[test]
+    fn zip_json_test() {
+        let items = [
+            Item {
+                name: "a\"b\t.txt",
+                crc32: 0xd87f7e0c,
+                data: b"test",
+                ..Default::default()
+            },
+            Item {
+                name: "c/",
+                comment: "note",
+                ..Default::default()
+            },
+        ];
+        let zip = process_bytes(build_zip(&items)).unwrap();
+        assert_eq!(
+            zip.json(false),
+            r#"[{"name":"a\"b\t.txt","is_dir":false,"offset":0,"compressed_size":4,"uncompressed_size":4,"crc32":3632233996,"method":0,"modified":"1980-01-01T00:00:00","comment":""},{"name":"c/","is_dir":true,"offset":42,"compressed_size":0,"uncompressed_size":0,"crc32":0,"method":0,"modified":"1980-01-01T00:00:00","comment":"note"}]"#,
+        );
+        assert_eq!(
+            zip.json(true),
+            r#"[
+  {
+    "name": "a\"b\t.txt",
+    "is_dir": false,
+    "offset": 0,
+    "compressed_size": 4,
+    "uncompressed_size": 4,
+    "crc32": 3632233996,
+    "method": 0,
+    "modified": "1980-01-01T00:00:00",
+    "comment": ""
+  },
+  {
+    "name": "c/",
+    "is_dir": true,
+    "offset": 42,
+    "compressed_size": 0,
+    "uncompressed_size": 0,
+    "crc32": 0,
+    "method": 0,
+    "modified": "1980-01-01T00:00:00",
+    "comment": "note"
+  }
+]"#,
+        );
+        let zip = process_bytes(build_zip(Layout::default())).unwrap();
+        assert_eq!(zip.json(false), "[]");
+        assert_eq!(zip.json(true), "[]");
+    }
+
+    #[test]
+    fn json_string_test() {
+        assert_eq!(json_string("a\"b\\c\n\u{1}"), r#""a\"b\\c\n\u0001""#);
+        assert_eq!(json_string("\u{398}"), "\"\u{398}\"");
+    }
+
+    #[test]
+    fn json_to_json_test() {
+        let value = Json::object([
+            ("a", Json::Array(vec![Json::Number(1), Json::Null])),
+            ("b", Json::object([])),
+            ("c", Json::Bool(true)),
+        ]);
+        assert_eq!(value.to_json(false), r#"{"a":[1,null],"b":{},"c":true}"#);
+        assert_eq!(
+            value.to_json(true),
+            "\
+{
+  \"a\": [
+    1,
+    null
+  ],
+  \"b\": {},
+  \"c\": true
+}",
+        );
+    }
+
+    #[test]
+    fn zip_layout_json_test() {
+        let items = [
+            Item {
+                name: "a.txt",
+                data: b"test",
+                ..Default::default()
+            },
+            Item {
+                name: "c/",
+                ..Default::default()
+            },
+        ];
+        let bytes = build_zip(Layout {
+            prefix: b"abc",
+            items: &items,
+            ..Default::default()
+        });
+        let zip = Zip::from_offset(&mut Cursor::new(bytes), 3).unwrap();
+        assert_eq!(
+            zip.layout_json(),
+            r#"{"a.txt":{"local_header_offset":3,"data_offset":38,"data_length":4,"central_header_offset":74},"c/":{"local_header_offset":42,"data_offset":74,"data_length":0,"central_header_offset":125}}"#,
+        );
+
+        // Central directory file header pointing past its local file header
+        let zip = process_bytes(build_zip(Layout {
+            items: &items[..1],
+            central: Some(&[(items[0], 1)]),
+            ..Default::default()
+        }))
+        .unwrap();
+        assert_eq!(
+            zip.layout_json(),
+            r#"{"a.txt":{"local_header_offset":1,"data_offset":null,"data_length":null,"central_header_offset":39}}"#,
+        );
+    }
+
+    #[test]
+    fn zip_entries_by_name_test() {
+        let items = [
+            Item {
+                name: "a.txt",
+                data: b"one",
+                ..Default::default()
+            },
+            Item {
+                name: "b.txt",
+                ..Default::default()
+            },
+            Item {
+                name: "a.txt",
+                data: b"two!",
+                ..Default::default()
+            },
+        ];
+        let zip = process_bytes(build_zip(&items)).unwrap();
+        let entries = zip.entries_by_name();
+        assert_eq!(
+            entries
+                .iter()
+                .map(|(name, i)| (name.as_str(), i.uncompressed_size()))
+                .collect::<Vec<_>>(),
+            vec![("a.txt", 3), ("b.txt", 0), ("a.txt", 4)],
+        );
+        assert_eq!(zip.find("a.txt").unwrap().uncompressed_size(), 3);
+    }
+
+    #[test]
+    fn zip_data_ranges_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let ranges = zip.data_ranges();
+        assert_eq!(ranges.len(), 10);
+        assert_eq!(
+            ranges[0],
+            (String::from("folder00/folder00-00/test00-00-00.txt"), 157, 4),
+        );
+        assert_eq!(ranges[5], (String::from("folder01/exercise.zip"), 697, 961));
+
+        // The data ranges locate each file's data
+        let bytes = std::fs::read("../exercise.zip").unwrap();
+        let (_, offset, length) = &ranges[0];
+        assert_eq!(&bytes[*offset as usize..(*offset + *length) as usize], b"test");
+        let (_, offset, length) = &ranges[5];
+        let data = &bytes[*offset as usize..(*offset + *length) as usize];
+        assert_eq!(data, zip.local_file_at(646).unwrap().file_data());
+    }
+
+    #[test]
+    fn central_directory_file_header_data_offset_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let mut r = File::open("../exercise.zip").unwrap();
+        for (name, offset, _) in zip.data_ranges() {
+            assert_eq!(zip.find(&name).unwrap().data_offset(&mut r), Ok(offset));
+        }
+
+        // The local extra field is longer than the central one
+        let item = Item {
+            name: "a.txt",
+            data: b"test",
+            extra: b"UT\x05\x00\x01\x00\x00\x00\x00",
+            ..Default::default()
+        };
+        let bytes = build_zip(Layout {
+            items: &[item],
+            central: Some(&[(Item { extra: b"", ..item }, 0)]),
+            ..Default::default()
+        });
+        let zip = process_bytes(bytes.clone()).unwrap();
+        let header = zip.find("a.txt").unwrap();
+        assert_eq!(header.data_offset(&mut Cursor::new(&bytes)), Ok(44));
+        assert_eq!(&bytes[44..48], b"test");
+
+        // Not a local file header
+        assert_eq!(
+            header.data_offset(&mut Cursor::new(vec![0; 30])),
+            Err(String::from("Invalid signature: `00000000`")),
+        );
+    }
+
+    #[test]
+    fn zip_entries_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let entries = zip.entries();
+        assert_eq!(entries.len(), 27);
+        assert!(matches!(entries[0], Entry::LocalFile(_)));
+        assert!(matches!(entries[13], Entry::CentralDirectoryFileHeader(_)));
+        assert!(matches!(entries[26], Entry::EndOfCentralDirectoryRecord(_)));
+    }
+
+    #[test]
+    fn zip_entries_with_offsets_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let entries = zip.entries_with_offsets().collect::<Vec<_>>();
+        assert_eq!(entries.len(), 27);
+        assert_eq!(
+            entries[..3].iter().map(|(o, _)| *o).collect::<Vec<_>>(),
+            vec![0, 39, 90],
+        );
+        assert!(matches!(entries[13], (1950, Entry::CentralDirectoryFileHeader(_))));
+        for (offset, entry) in zip.entries_with_offsets() {
+            if let Entry::LocalFile(i) = entry {
+                assert!(std::ptr::eq(zip.local_file_at(offset).unwrap(), i));
+            }
+        }
+    }
+
+    // Tree
+
+    #[test]
+    fn zip_tree_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        assert_eq!(
+            zip.tree(),
+            "\
+folder00/
+├── folder00-00/
+│   ├── test00-00-00.txt
+│   ├── test00-00-01.txt
+│   └── test00-00-02.txt
+├── test00-00.txt
+└── test00-01.txt
+folder01/
+├── exercise.zip
+└── test01-00.txt
+test00.txt
+test01.txt
+test02.txt
+",
+        );
+        let options = TreeOptions {
+            style: TreeStyle::Ascii,
+            sizes: true,
+            dates: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            zip.tree_with(&options),
+            "\
+folder00/ (2022-05-19T10:51:38)
+|-- folder00-00/ (2022-05-19T10:51:18)
+|   |-- test00-00-00.txt (4, 2020-08-25T09:05:38)
+|   |-- test00-00-01.txt (125, 2022-05-19T10:56:30)
+|   `-- test00-00-02.txt (4, 2020-08-25T09:05:38)
+|-- test00-00.txt (95, 2022-05-19T10:57:24)
+`-- test00-01.txt (0, 2021-08-25T13:04:38)
+folder01/ (2022-05-19T10:51:26)
+|-- exercise.zip (2272, 2022-05-19T11:05:08)
+`-- test01-00.txt (127, 2022-05-19T10:53:46)
+test00.txt (4, 2020-08-25T09:05:38)
+test01.txt (4, 2020-08-25T09:05:38)
+test02.txt (4, 2020-08-25T09:05:38)
+",
+        );
+    }
+
+    #[test]
+    fn zip_tree_max_depth_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let options = TreeOptions {
+            max_depth: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            zip.tree_with(&options),
+            "\
+folder00/
+└── ... (6 items below the maximum depth of 1)
+folder01/
+└── ... (2 items below the maximum depth of 1)
+test00.txt
+test01.txt
+test02.txt
+",
+        );
+        let options = TreeOptions {
+            max_depth: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(
+            zip.tree_with(&options),
+            "... (13 items below the maximum depth of 0)\n",
+        );
+
+        // Far deeper than the default maximum depth
+        let name = format!("{}a.txt", "a/".repeat(30000));
+        let item = Item {
+            name: &name,
+            ..Default::default()
+        };
+        let tree = process_bytes(build_zip(&[item])).unwrap().tree();
+        let lines = tree.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), DEFAULT_MAX_DEPTH + 1);
+        assert!(
+            lines[DEFAULT_MAX_DEPTH].ends_with("└── ... (1 item below the maximum depth of 16)")
+        );
+    }
+
+    #[test]
+    fn is_executable_test() {
+        // Made on MS-DOS (host 0), so there are no Unix modes
+        let zip = Zip::from("../exercise.zip").unwrap();
+        assert!(zip
+            .central_directory()
+            .all(|i| i.unix_mode().is_none() && i.is_executable().is_none()));
+
+        // Made on Unix (host 3) with mode 0o600
+        let zip = Zip::from("../streamed.zip").unwrap();
+        assert!(zip
+            .central_directory()
+            .all(|i| i.unix_mode() == Some(0o600) && i.is_executable() == Some(false)));
+
+        let items = [
+            Item {
+                name: "bin/",
+                ..Default::default()
+            },
+            Item {
+                name: "bin/run.sh",
+                data: b"#!/bin/sh\n",
+                ..Default::default()
+            },
+            Item {
+                name: "readme.txt",
+                ..Default::default()
+            },
+        ];
+        let mut bytes = build_zip(&items);
+        let offsets = bytes
+            .windows(4)
+            .enumerate()
+            .filter(|(_, w)| *w == b"PK\x01\x02")
+            .map(|(n, _)| n)
+            .collect::<Vec<_>>();
+        for (n, mode) in offsets.into_iter().zip([0o40755_u32, 0o100755, 0o100644]) {
+            bytes[n + 5] = 3;
+            bytes[n + 38..n + 42].copy_from_slice(&(mode << 16).to_le_bytes());
+        }
+        let zip = process_bytes(bytes).unwrap();
+        assert_eq!(
+            zip.central_directory()
+                .map(|i| (i.unix_mode(), i.is_executable()))
+                .collect::<Vec<_>>(),
+            vec![
+                (Some(0o40755), Some(false)),
+                (Some(0o100755), Some(true)),
+                (Some(0o100644), Some(false)),
+            ],
+        );
+        let options = SummaryOptions {
+            executable: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            zip.summary_with(&options).unwrap(),
+            "\
+bin/\ttrue\t0\t1980-01-01T00:00:00\t\t
+bin/run.sh\tfalse\t10\t1980-01-01T00:00:00\t\t(exec)
+readme.txt\tfalse\t0\t1980-01-01T00:00:00\t\t
+",
+        );
+        let options = TreeOptions {
+            executable: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            zip.tree_with(&options),
+            "\
+bin/
+└── run.sh (exec)
+readme.txt
+",
+        );
+    }
+
+    #[test]
+    fn tree_style_test() {
+        assert_eq!("ascii".parse(), Ok(TreeStyle::Ascii));
+        assert_eq!("Unicode".parse(), Ok(TreeStyle::Unicode));
+        assert_eq!(
+            "box".parse::<TreeStyle>().unwrap_err(),
+            String::from("Unknown tree style: `box` (expected `unicode` or `ascii`)"),
         );
     }
+
+    #[test]
+    fn zip_largest_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let largest = |n: usize| {
+            zip.largest(n)
+                .iter()
+                .map(|i| (i.name(Encoding::Auto).into_owned(), i.uncompressed_size()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            largest(3),
+            [
+                (String::from("folder01/exercise.zip"), 2272),
+                (String::from("folder01/test01-00.txt"), 127),
+                (String::from("folder00/folder00-00/test00-00-01.txt"), 125),
+            ],
+        );
+
+        // Folders are left out and files of the same size keep their order
+        let all = largest(100);
+        assert_eq!(all.len(), 10);
+        assert_eq!(all[4].0, "folder00/folder00-00/test00-00-00.txt");
+        assert_eq!(all[9], (String::from("folder00/test00-01.txt"), 0));
+        assert!(zip.largest(0).is_empty());
+    }
+
+    #[test]
+    fn zip_directory_sizes_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        assert_eq!(
+            zip.directory_sizes(),
+            BTreeMap::from([
+                (String::from("folder00/"), 228),
+                (String::from("folder00/folder00-00/"), 133),
+                (String::from("folder01/"), 2399),
+            ]),
+        );
+    }
+
+    #[test]
+    fn zip_directory_sizes_implicit_test() {
+        let zip = process_bytes(build_zip(&[
+            Item {
+                name: "a/b/c.txt",
+                data: b"test",
+                ..Default::default()
+            },
+            Item {
+                name: "a/d.txt",
+                data: b"hello",
+                ..Default::default()
+            },
+        ]))
+        .unwrap();
+        assert_eq!(
+            zip.directory_sizes(),
+            BTreeMap::from([(String::from("a/"), 9), (String::from("a/b/"), 4)]),
+        );
+    }
+
+    #[test]
+    fn tree_folder_sizes_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let options = TreeOptions {
+            sizes: true,
+            folder_sizes: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            zip.tree_with(&options),
+            "\
+folder00/ (228)
+├── folder00-00/ (133)
+│   ├── test00-00-00.txt (4)
+│   ├── test00-00-01.txt (125)
+│   └── test00-00-02.txt (4)
+├── test00-00.txt (95)
+└── test00-01.txt (0)
+folder01/ (2399)
+├── exercise.zip (2272)
+└── test01-00.txt (127)
+test00.txt (4)
+test01.txt (4)
+test02.txt (4)
+",
+        );
+    }
+
+    #[test]
+    fn zip_implied_directories_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        assert!(zip.implied_directories().is_empty());
+
+        // Only `e/` has an entry of its own
+        let item = Item {
+            data: b"test",
+            ..Default::default()
+        };
+        let zip = process_bytes(build_zip(&[
+            Item {
+                name: "a/b/c.txt",
+                ..item
+            },
+            Item {
+                name: "a/d.txt",
+                ..item
+            },
+            Item {
+                name: "e/",
+                data: b"",
+                ..item
+            },
+            Item {
+                name: "e/f.txt",
+                ..item
+            },
+        ]))
+        .unwrap();
+        assert_eq!(
+            zip.implied_directories(),
+            BTreeSet::from([String::from("a/"), String::from("a/b/")]),
+        );
+        assert_eq!(
+            zip.tree(),
+            "\
+a/
+├── b/
+│   └── c.txt
+└── d.txt
+e/
+└── f.txt
+",
+        );
+        let plan = zip.extract_plan(Path::new("out"));
+        assert_eq!(
+            plan.iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>(),
+            ["a/", "a/b/", "a/b/c.txt", "a/d.txt", "e/", "e/f.txt"],
+        );
+        assert_eq!(plan[1].1, Ok(PathBuf::from("out/a/b")));
+    }
+
+    #[test]
+    fn zip_process_eof_test() {
+        let bytes = hex::decode("00").unwrap();
+        let cursor = Cursor::new(&bytes);
+        let mut reader = BufReader::new(cursor);
+        assert_eq!(
+            Zip::process(&mut reader).unwrap_err(),
+            String::from("Unexpected end of file"),
+        );
+    }
+
+    #[test]
+    fn zip_locate_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let mut r = File::open("../exercise.zip").unwrap();
+        for (name, offset, length) in zip.data_ranges() {
+            let (header, data, size) = Zip::locate(&mut r, &name).unwrap().unwrap();
+            assert_eq!(header.name(Encoding::Auto), name);
+            assert_eq!((data, size), (offset, length));
+        }
+        assert!(Zip::locate(&mut r, "missing.txt").unwrap().is_none());
+        assert_eq!(r.stream_position().unwrap(), 0);
+
+        // Offsets are relative to the starting position
+        let item = Item {
+            name: "a.txt",
+            data: b"test",
+            ..Default::default()
+        };
+        let mut r = Cursor::new(build_zip(Layout {
+            prefix: b"stub",
+            items: &[item],
+            ..Default::default()
+        }));
+        r.seek(SeekFrom::Start(4)).unwrap();
+        let (header, data, size) = Zip::locate(&mut r, "a.txt").unwrap().unwrap();
+        assert_eq!(header.lfh_offset(), 0);
+        assert_eq!((data, size), (39, 4));
+        assert_eq!(r.stream_position().unwrap(), 4);
+
+        // Not a zip file
+        let mut r = Cursor::new(b"not a zip file".to_vec());
+        assert_eq!(
+            Zip::locate(&mut r, "a.txt").unwrap_err(),
+            "End of central directory record not found",
+        );
+    }
+
+    #[test]
+    fn zip_probe_test() {
+        let mut f = File::open("../exercise.zip").unwrap();
+        assert_eq!(
+            Zip::probe(&mut f).unwrap(),
+            ProbeResult {
+                zip: true,
+                zip64: false,
+                name: Some(String::from("folder00/")),
+                compression: Some(0),
+            },
+        );
+        assert_eq!(f.stream_position().unwrap(), 0);
+
+        // Empty zip file
+        let mut r = Cursor::new(build_zip(Layout::default()));
+        assert_eq!(
+            Zip::probe(&mut r).unwrap(),
+            ProbeResult {
+                zip: true,
+                ..Default::default()
+            },
+        );
+
+        // Saturated end of central directory record fields
+        let mut r = Cursor::new(build_zip(Layout {
+            entries: Some(0xffff),
+            ..Default::default()
+        }));
+
+        assert!(Zip::probe(&mut r).unwrap().zip64);
+
+        // Not a zip file
+        let mut r = Cursor::new(hex::decode("1f8b0800000000000003").unwrap());
+        assert_eq!(Zip::probe(&mut r).unwrap(), ProbeResult::default());
+        let mut r = Cursor::new(vec![]);
+        assert_eq!(Zip::probe(&mut r).unwrap(), ProbeResult::default());
+    }
+
+    #[test]
+    fn zip_process_non_zip_test() {
+        let mut tar = vec![0; 512];
+        tar[257..262].copy_from_slice(b"ustar");
+        for (format, bytes) in [
+            ("gzip", hex::decode("1f8b0800000000000003").unwrap()),
+            ("tar", tar),
+            ("7z", hex::decode("377abcaf271c0004").unwrap()),
+            ("RAR", hex::decode("526172211a070100").unwrap()),
+        ] {
+            let want = format!("This looks like a {format} file, not a zip archive");
+            assert_eq!(process_bytes(bytes.clone()).unwrap_err(), want);
+            let mut reader = BufReader::new(Cursor::new(&bytes));
+            let zip = Zip::process_lenient(&mut reader).unwrap();
+            assert_eq!(zip.truncated(), Some(want.as_str()));
+            assert_eq!(zip.trailing_bytes(), bytes.len() as u64);
+        }
+    }
+
+    #[test]
+    fn zip_process_invalid_sig_test() {
+        let bytes = hex::decode("00000001").unwrap();
+        let cursor = Cursor::new(&bytes);
+        let mut reader = BufReader::new(cursor);
+        assert_eq!(
+            Zip::process(&mut reader).unwrap_err(),
+            String::from("Invalid signature: `00000001`"),
+        );
+    }
+
+    #[test]
+    fn zip_process_prefix_test() {
+        let item = Item {
+            name: "a.txt",
+            data: b"test",
+            ..Default::default()
+        };
+        let want = "Invalid signature: `ffffffff`; the zip file starts after a 100-byte prefix, \
+            likely a self-extracting stub (parse it from offset 100)";
+        for adjusted in [false, true] {
+            let bytes = build_zip(Layout {
+                prefix: &[0xff; 100],
+                adjusted,
+                items: &[item],
+                ..Default::default()
+            });
+            assert_eq!(Zip::from_bytes(&bytes).unwrap_err(), want);
+            let mut r = BufReader::new(Cursor::new(bytes.clone()));
+            let zip = Zip::process_lenient(&mut r).unwrap();
+            assert_eq!(zip.truncated(), Some(want));
+            let zip = Zip::from_offset(&mut Cursor::new(bytes), 100).unwrap();
+            assert_eq!(zip.names(), vec!["a.txt"]);
+        }
+
+        // Damage without a zip file after it is reported as before
+        let mut bytes = vec![0xff; 100];
+        bytes.extend(build_zip(&[item]));
+        bytes.truncate(bytes.len() - 22);
+        assert_eq!(
+            Zip::from_bytes(&bytes).unwrap_err(),
+            "Invalid signature: `ffffffff`",
+        );
+    }
+
+    #[test]
+    fn zip_process_lenient_test() {
+        let item = Item {
+            name: "a.txt",
+            data: b"test",
+            ..Default::default()
+        };
+        let bytes = build_zip(Layout {
+            items: &[item],
+            between: b"damaged",
+            omit_eocd: true,
+            ..Default::default()
+        });
+        let size = item.local().len() as u64;
+        let mut r = BufReader::new(Cursor::new(bytes.clone()));
+        assert_eq!(
+            Zip::process(&mut r).unwrap_err(),
+            String::from("Invalid signature: `64616d61`"),
+        );
+        let mut r = BufReader::new(Cursor::new(bytes.clone()));
+        let zip = Zip::process_lenient(&mut r).unwrap();
+        assert_eq!(zip.entries().len(), 1);
+        assert_eq!(zip.truncated(), Some("Invalid signature: `64616d61`"));
+        assert_eq!(zip.trailing_bytes(), bytes.len() as u64 - size);
+    }
+
+    #[test]
+    fn zip_process_inflated_length_test() {
+        let item = Item {
+            name: "a.txt",
+            data: b"test",
+            ..Default::default()
+        };
+
+        // Central directory file header file comment length that runs into the end of central
+        // directory record and past the end of the file
+        let mut bytes = build_zip(&[item]);
+        bytes[39 + 32..39 + 34].copy_from_slice(&1000u16.to_le_bytes());
+        let e = "Central directory file header (entry 1) at offset 39: file_comment_length (1000) \
+            exceeds the 22 remaining bytes";
+        assert_eq!(process_bytes(bytes.clone()).unwrap_err(), e);
+        let zip = Zip::process_lenient(&mut BufReader::new(Cursor::new(bytes))).unwrap();
+        assert_eq!(zip.counts().central_directory_file_headers, 0);
+        assert_eq!(zip.truncated(), Some(e));
+
+        // End of central directory record comment length
+        let item = Item {
+            name: "a.txt",
+            data: b"test",
+            ..Default::default()
+        };
+        let mut bytes = build_zip(&[item]);
+        let len = bytes.len();
+        bytes[len - 2..].copy_from_slice(&50u16.to_le_bytes());
+        let e = "End of central directory record (entry 2) at offset 90: comment_length (50) \
+            exceeds the 0 remaining bytes";
+        assert_eq!(process_bytes(bytes.clone()).unwrap_err(), e);
+        let zip = Zip::process_lenient(&mut BufReader::new(Cursor::new(bytes))).unwrap();
+        assert_eq!(zip.truncated(), Some(e));
+
+        // Local file header file name length in the first record
+        let item = Item {
+            name: "a.txt",
+            data: b"test",
+            ..Default::default()
+        };
+        let mut bytes = build_zip(Layout {
+            items: &[item],
+            central: Some(&[]),
+            omit_eocd: true,
+            ..Default::default()
+        });
+        bytes[26..28].copy_from_slice(&200u16.to_le_bytes());
+        assert_eq!(
+            process_bytes(bytes).unwrap_err(),
+            "Local file header (entry 0) at offset 0: file_name_length (200) exceeds the 9 \
+            remaining bytes",
+        );
+    }
+
+    #[test]
+    fn zip_from_bytes_test() {
+        let bytes = std::fs::read("../exercise.zip").unwrap();
+        assert_eq!(Zip::from_bytes(&bytes).unwrap().names().len(), 13);
+
+        // A forged compressed size is rejected before the file data is allocated
+        let mut forged = bytes.clone();
+        forged[18..22].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            Zip::from_bytes(&forged).unwrap_err(),
+            "Local file header (entry 0) at offset 0: compressed_size (4294967295) exceeds the \
+            3348 remaining bytes",
+        );
+
+        // Truncated and damaged input is an `Ok` or an `Err`, never a panic
+        for n in 0..bytes.len() {
+            if let Ok(zip) = Zip::from_bytes(&bytes[..n]) {
+                zip.summary().unwrap();
+            }
+            let mut damaged = bytes.clone();
+            damaged[n] ^= 0xff;
+            if let Ok(zip) = Zip::from_bytes(&damaged) {
+                zip.pretty().unwrap();
+                zip.check();
+                zip.audit();
+            }
+        }
+    }
+
+    #[test]
+    fn magic_hex_test() {
+        assert_eq!(magic_hex("[80, 75, 3, 4]"), "504b0304");
+        assert_eq!(magic_hex("[80, 256, x]"), "[80, 256, x]");
+        assert_eq!(magic_hex("[80, 75"), "[80, 75");
+        assert_eq!(magic_hex("[]"), "[]");
+    }
+
+    #[test]
+    fn zip_process_lenient_empty_test() {
+        let mut r = BufReader::new(Cursor::new(vec![]));
+        let zip = Zip::process_lenient(&mut r).unwrap();
+        assert!(zip.entries().is_empty());
+        assert_eq!(zip.truncated(), Some("Unexpected end of file"));
+        let zip = Zip::from("../exercise.zip").unwrap();
+        assert_eq!(zip.truncated(), None);
+    }
+
+    #[test]
+    fn zip_process_multi_test() {
+        let a = Item {
+            name: "a.txt",
+            data: b"test",
+            ..Default::default()
+        };
+        let b = Item { name: "b.txt", ..a };
+        let mut bytes = build_zip(&[a]);
+        let size = bytes.len() as u64;
+        bytes.extend(build_zip(&[b]));
+        bytes.extend(b"junk");
+        let mut r = BufReader::new(Cursor::new(bytes.clone()));
+        let zips = Zip::process_multi(&mut r).unwrap();
+        assert_eq!(zips.len(), 2);
+        assert_eq!(zips[0].names(), ["a.txt"]);
+        assert_eq!((zips[0].base_offset(), zips[0].trailing_bytes()), (0, 0));
+        assert_eq!(zips[1].names(), ["b.txt"]);
+        assert_eq!((zips[1].base_offset(), zips[1].trailing_bytes()), (size, 4));
+
+        // The reader options apply to each zip file
+        let mut r = BufReader::new(Cursor::new(bytes.clone()));
+        let zips = Zip::reader()
+            .encoding(Encoding::Utf8)
+            .process_multi(&mut r)
+            .unwrap();
+        assert!(zips.iter().all(|zip| zip.encoding() == Encoding::Utf8));
+        let mut r = BufReader::new(Cursor::new(bytes.clone()));
+        assert!(Zip::reader().strict(true).process_multi(&mut r).is_err());
+
+        // A single zip file
+        let zips = Zip::from_multi("../exercise.zip").unwrap();
+        assert_eq!(zips.len(), 1);
+        assert_eq!(zips[0].summary().unwrap(), SUMMARY);
+
+        // A damaged zip file after the first one
+        bytes.truncate(size as usize + 10);
+        let mut r = BufReader::new(Cursor::new(bytes));
+        assert_eq!(
+            Zip::process_multi(&mut r).unwrap_err(),
+            "Unexpected end of file",
+        );
+    }
+
+    #[test]
+    fn zip_from_offset_test() {
+        let bytes = build_zip(Layout {
+            prefix: &[0xff; 100],
+            items: &[Item {
+                name: "a.txt",
+                data: b"test",
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        let mut cursor = Cursor::new(bytes);
+        let zip = Zip::from_offset(&mut cursor, 100).unwrap();
+        assert_eq!(zip.base_offset(), 100);
+        assert!(zip.local_file_at(100).is_some());
+        assert_eq!(zip.audit(), vec![]);
+        assert_eq!(zip.nonconformances(), Vec::<String>::new());
+        assert_eq!(
+            zip.summary().unwrap(),
+            "a.txt\tfalse\t4\t1980-01-01T00:00:00\t\n",
+        );
+    }
+
+    #[test]
+    fn zip_from_entries_test() {
+        let bytes = std::fs::read("../exercise.zip").unwrap();
+        let entries = Cursor::new(bytes).read_le::<Entries>().unwrap();
+        let zip = Zip::from_entries(entries.list);
+        assert_eq!(zip.summary().unwrap(), SUMMARY);
+        assert_eq!(zip.verbose().unwrap(), VERBOSE);
+        assert!(zip.local_file_at(1818).is_some());
+
+        let zip = Zip::from_entries(vec![]);
+        assert_eq!(zip.entries().len(), 0);
+        assert_eq!(zip.summary().unwrap(), "");
+    }
+
+    #[test]
+    fn zip_process_from_test() {
+        let mut r = BufReader::new(File::open("../exercise.zip").unwrap());
+        let zip = Zip::process_from(&mut r, 1950).unwrap();
+        assert_eq!(zip.base_offset(), 0);
+        assert_eq!(zip.entries().len(), 14);
+        assert_eq!(zip.entries_with_offsets().next().unwrap().0, 1950);
+        assert_eq!(zip.summary().unwrap(), SUMMARY);
+        let local_files = zip
+            .central_directory()
+            .filter_map(|i| zip.local_file_for(i));
+        assert_eq!(local_files.count(), 0);
+        assert_eq!(zip.trailing_bytes(), 0);
+
+        // Zip file after a prefix, with the reader at the base offset
+        let mut bytes = vec![0xff; 100];
+        bytes.extend(std::fs::read("../exercise.zip").unwrap());
+        let mut r = BufReader::new(Cursor::new(bytes));
+        r.seek(SeekFrom::Start(100)).unwrap();
+        let zip = Zip::process_from(&mut r, 1950).unwrap();
+        assert_eq!(zip.base_offset(), 100);
+        assert_eq!(zip.entries_with_offsets().next().unwrap().0, 2050);
+        assert_eq!(zip.summary().unwrap(), SUMMARY);
+
+        r.seek(SeekFrom::Start(0)).unwrap();
+        assert_eq!(
+            Zip::process_from(&mut r, 5000).unwrap_err(),
+            "Unexpected end of file",
+        );
+    }
+
+    #[test]
+    fn entry_header_bytes_test() {
+        let bytes = std::fs::read("../exercise.zip").unwrap();
+        let zip = Zip::from("../exercise.zip").unwrap();
+        for (offset, entry) in zip.entries_with_offsets() {
+            let header = entry.header_bytes();
+            let start = offset as usize;
+            assert_eq!(header, &bytes[start..start + header.len()]);
+            if let Entry::LocalFile(i) = entry {
+                assert_eq!(header.len() as u64, i.data_offset());
+            } else {
+                assert_eq!(header.len() as u64, entry.record_size());
+            }
+        }
+    }
+
+    #[test]
+    fn zip_archive_extra_data_record_test() {
+        let item = Item {
+            name: "a.txt",
+            data: b"test",
+            ..Default::default()
+        };
+        let record = b"PK\x06\x08\x04\x00\x00\x00\x99\x99\x00\x00";
+        let zip = process_bytes(build_zip(Layout {
+            items: &[item],
+            between: record,
+            ..Default::default()
+        }))
+        .unwrap();
+        assert_eq!(zip.entries().len(), 4);
+        match &zip.entries()[1] {
+            Entry::ArchiveExtraDataRecord(i) => {
+                assert_eq!(i.record_size(), record.len() as u64);
+                assert_eq!(i.header_bytes(), record);
+                assert_eq!(
+                    i.extra_fields(),
+                    Ok(vec![ExtraField {
+                        tag: 0x9999,
+                        data: vec![],
+                    }]),
+                );
+                assert_eq!(
+                    i.verbose(),
+                    "\
+sig = 0x504b0608 (Archive extra data record)
+extra_field_length = 0x00000004 (4)
+extra_field = \"99990000\"
+",
+                );
+            }
+            _ => panic!("expected an archive extra data record"),
+        }
+        assert_eq!(zip.strict(), Ok(()));
+        assert_eq!(zip.names(), vec!["a.txt"]);
+    }
+
+    // Audit
+
+    #[test]
+    fn zip_audit_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        assert_eq!(zip.audit(), vec![]);
+    }
+
+    #[test]
+    fn zip_audit_name_mismatch_test() {
+        let local = Item {
+            name: "a.txt",
+            data: b"test",
+            ..Default::default()
+        };
+        let central = Item {
+            name: "b.txt",
+            data: b"test",
+            ..Default::default()
+        };
+        let zip = process_bytes(build_zip(Layout {
+            items: &[local],
+            central: Some(&[(central, 0)]),
+            ..Default::default()
+        }))
+        .unwrap();
+        assert_eq!(
+            zip.audit(),
+            vec![AuditFinding::NameMismatch {
+                offset: 0,
+                local: String::from("a.txt"),
+                central: String::from("b.txt"),
+            }],
+        );
+    }
+
+    #[test]
+    fn zip_audit_compression_mismatch_test() {
+        let local = Item {
+            name: "a.txt",
+            compression: 8,
+            crc32: 0xd87f7e0c,
+            data: b"+I-.\x01\x00",
+            ..Default::default()
+        };
+        let central = Item {
+            compression: 0,
+            ..local
+        };
+        let zip = process_bytes(build_zip(Layout {
+            items: &[local],
+            central: Some(&[(central, 0)]),
+            ..Default::default()
+        }))
+        .unwrap();
+        assert_eq!(
+            zip.audit(),
+            vec![AuditFinding::CompressionMismatch {
+                offset: 0,
+                name: String::from("a.txt"),
+                local: 8,
+                central: 0,
+            }],
+        );
+        assert_eq!(
+            zip.audit()[0].to_string(),
+            "Local file `a.txt` at offset 0 uses compression method 8 (deflated) but the central \
+            directory file header says 0 (stored)",
+        );
+    }
+
+    #[test]
+    fn zip_audit_missing_local_file_test() {
+        let item = Item {
+            name: "a.txt",
+            data: b"test",
+            ..Default::default()
+        };
+        let zip = process_bytes(build_zip(Layout {
+            items: &[item],
+            central: Some(&[(item, 1)]),
+            ..Default::default()
+        }))
+        .unwrap();
+        assert_eq!(
+            zip.audit(),
+            vec![AuditFinding::MissingLocalFile {
+                offset: 1,
+                name: String::from("a.txt"),
+            }],
+        );
+        assert_eq!(
+            zip.audit()[0].to_string(),
+            "No local file header at offset 1 for `a.txt`",
+        );
+    }
+
+    // Encryption
+
+    #[test]
+    fn encryption_test() {
+        let zip_crypto = Item {
+            name: "a.txt",
+            flags: 1,
+            data: b"test",
+            ..Default::default()
+        };
+        let aes = Item {
+            name: "b.txt",
+            flags: 1,
+            compression: 99,
+            data: b"test",
+            extra: b"\x01\x99\x07\x00\x02\x00AE\x03\x08\x00",
+            ..Default::default()
+        };
+        let plain = Item {
+            name: "c.txt",
+            data: b"test",
+            ..Default::default()
+        };
+        let zip = process_bytes(build_zip(&[zip_crypto, aes, plain])).unwrap();
+        assert_eq!(
+            zip.central_directory()
+                .map(|i| i.encryption())
+                .collect::<Vec<_>>(),
+            vec![
+                Encryption::ZipCrypto,
+                Encryption::Aes { bits: 256 },
+                Encryption::None,
+            ],
+        );
+        let options = SummaryOptions {
+            encryption: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            zip.summary_with(&options).unwrap(),
+            "\
+a.txt\tfalse\t4\t1980-01-01T00:00:00\t\tZipCrypto
+b.txt\tfalse\t4\t1980-01-01T00:00:00\t\tAES-256
+c.txt\tfalse\t4\t1980-01-01T00:00:00\t\tnone
+",
+        );
+        let options = SummaryOptions {
+            method: true,
+            encryption: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            zip.summary_with(&options).unwrap(),
+            "\
+a.txt\tfalse\t4\t1980-01-01T00:00:00\t\tstored\tZipCrypto
+b.txt\tfalse\t4\t1980-01-01T00:00:00\t\tAES\tAES-256
+c.txt\tfalse\t4\t1980-01-01T00:00:00\t\tstored\tnone
+",
+        );
+    }
+
+    #[test]
+    fn strong_encryption_test() {
+        let strong = Item {
+            name: "a.txt",
+            flags: 1 | (1 << 6),
+            data: b"test",
+            ..Default::default()
+        };
+        let bytes = build_zip(&[strong]);
+        assert_eq!(
+            process_bytes(bytes.clone()).unwrap_err(),
+            "Strong encryption is not supported: `a.txt`",
+        );
+        let zip = Zip::process_lenient(&mut BufReader::new(Cursor::new(bytes))).unwrap();
+        assert!(zip.entries().is_empty());
+        assert_eq!(
+            zip.truncated(),
+            Some("Strong encryption is not supported: `a.txt`"),
+        );
+
+        // Masked local header of an encrypted central directory
+        let masked = Item {
+            name: "b.txt",
+            flags: 1 << 13,
+            data: b"test",
+            ..Default::default()
+        };
+        assert_eq!(
+            process_bytes(build_zip(&[masked])).unwrap_err(),
+            "Strong encryption is not supported: `b.txt`",
+        );
+
+        // Only the central directory file header sets the flag
+        let item = Item {
+            name: "c.txt",
+            flags: 1,
+            data: b"test",
+            ..Default::default()
+        };
+        let central = Item {
+            flags: 1 | (1 << 6),
+            ..item
+        };
+        let zip = process_bytes(build_zip(Layout {
+            items: &[item],
+            central: Some(&[(central, 0)]),
+            ..Default::default()
+        }))
+        .unwrap();
+        let header = zip.central_directory().next().unwrap();
+        assert_eq!(header.encryption(), Encryption::Strong);
+        assert_eq!(header.encryption().to_string(), "strong");
+    }
+
+    // Check
+
+    #[test]
+    fn zip_check_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        assert_eq!(zip.check(), vec![]);
+    }
+
+    #[test]
+    fn zip_check_utf8_flag_test() {
+        let invalid = Item {
+            name: "a\u{fffd}.txt",
+            flags: 1 << 11,
+            ..Default::default()
+        };
+        let mut bytes = build_zip(&[invalid]);
+        // Replace the UTF-8 encoding of U+FFFD with a lone continuation byte and padding
+        for i in 0..bytes.len() - 2 {
+            if bytes[i..i + 3] == [0xef, 0xbf, 0xbd] {
+                bytes.splice(i..i + 3, [0x80, b'_', b'_']);
+            }
+        }
+        let zip = process_bytes(bytes).unwrap();
+        let name = String::from("61805f5f2e747874");
+        assert_eq!(
+            zip.check(),
+            vec![
+                CheckFinding::InvalidUtf8Name {
+                    offset: 0,
+                    name: name.clone(),
+                },
+                CheckFinding::InvalidUtf8Name { offset: 38, name },
+            ],
+        );
+        assert_eq!(
+            zip.check()[0].to_string(),
+            "File name `61805f5f2e747874` (hex) at offset 0 sets the UTF-8 flag but is not valid \
+            UTF-8",
+        );
+
+        let unflagged = Item {
+            name: "\u{e9}.txt",
+            ..Default::default()
+        };
+        let zip = process_bytes(build_zip(&[unflagged])).unwrap();
+        assert_eq!(
+            zip.check(),
+            vec![
+                CheckFinding::UnflaggedUtf8Name {
+                    offset: 0,
+                    name: String::from("c3a92e747874"),
+                },
+                CheckFinding::UnflaggedUtf8Name {
+                    offset: 36,
+                    name: String::from("c3a92e747874"),
+                },
+            ],
+        );
+
+        let flagged = Item {
+            name: "\u{e9}.txt",
+            flags: 1 << 11,
+            ..Default::default()
+        };
+        let zip = process_bytes(build_zip(&[flagged])).unwrap();
+        assert_eq!(zip.check(), vec![]);
+    }
+
+    #[test]
+    fn zip_check_cd_offset_test() {
+        let item = Item {
+            name: "a.txt",
+            data: b"test",
+            ..Default::default()
+        };
+        let zip = process_bytes(build_zip(Layout {
+            items: &[item],
+            cd_offset: Some(30),
+            ..Default::default()
+        }))
+        .unwrap();
+        let finding = CheckFinding::CdOffsetMismatch {
+            declared: 30,
+            actual: 39,
+            stub: None,
+        };
+        assert_eq!(zip.check(), vec![finding.clone()]);
+        assert_eq!(
+            finding.to_string(),
+            "End of central directory record declares the central directory at offset 30 but it \
+            starts at offset 39",
+        );
+
+        // Self-extracting archive whose offsets include the stub
+        let bytes = build_zip(Layout {
+            prefix: &[0xff; 100],
+            adjusted: true,
+            items: &[item],
+            ..Default::default()
+        });
+        let zip = Zip::from_offset(&mut Cursor::new(bytes), 100).unwrap();
+        let finding = CheckFinding::CdOffsetMismatch {
+            declared: 139,
+            actual: 39,
+            stub: Some(100),
+        };
+        assert_eq!(zip.check(), vec![finding.clone()]);
+        assert_eq!(
+            finding.to_string(),
+            "End of central directory record declares the central directory at offset 139 but it \
+            starts at offset 39 (the difference is the 100-byte prefix; likely a self-extracting \
+            archive rather than corruption)",
+        );
+    }
+
+    #[test]
+    fn zip_check_lfh_offset_order_test() {
+        let a = Item {
+            name: "a.txt",
+            data: b"test",
+            ..Default::default()
+        };
+        let b = Item { name: "b.txt", ..a };
+        let zip = process_bytes(build_zip(Layout {
+            items: &[a, b],
+            central: Some(&[(b, 39), (a, 0)]),
+            ..Default::default()
+        }))
+        .unwrap();
+        let finding = CheckFinding::LfhOffsetOrder {
+            name: String::from("a.txt"),
+            lfh_offset: 0,
+            previous: String::from("b.txt"),
+            previous_lfh_offset: 39,
+        };
+        assert_eq!(zip.check(), vec![finding.clone()]);
+        assert_eq!(
+            finding.to_string(),
+            "Central directory file header `a.txt` has lfh_offset 0, lower than 39 of the previous \
+            header `b.txt`",
+        );
+    }
+
+    #[test]
+    fn local_file_data_descriptor_test() {
+        let item = Item {
+            name: "a.txt",
+            flags: 1 << 3,
+            data: b"test",
+            descriptor: Some([0xd87f7e0c, 4, 4]),
+            ..Default::default()
+        };
+        let zip = process_bytes(build_zip(&[item])).unwrap();
+        let local = zip.local_file_at(0).unwrap();
+        assert_eq!(local.crc32(), 0xd87f7e0c);
+        assert_eq!(local.compressed_size(), 4);
+        assert_eq!(local.uncompressed_size(), 4);
+        assert_eq!(zip.check(), vec![]);
+        assert!(local
+            .verbose()
+            .contains("crc32 = 0x00000000 (0) [data descriptor: 0xd87f7e0c (3632233996)]\n"));
+        assert!(local
+            .verbose()
+            .contains("compressed_size = 0x00000004 (4)\n"));
+    }
+
+    #[test]
+    fn local_file_streamed_test() {
+        // Written by Python's `zipfile` to a pipe
+        let zip = Zip::from("../streamed.zip").unwrap();
+        assert_eq!(
+            zip.summary().unwrap(),
+            "\
+deflated.txt\tfalse\t184\t2022-05-19T10:51:38\t
+stored.txt\tfalse\t25\t2022-05-19T10:51:38\t
+empty.txt\tfalse\t0\t2022-05-19T10:51:38\t
+",
+        );
+        assert!(zip.is_streamed());
+        assert_eq!(zip.truncated(), None);
+        assert_eq!(zip.trailing_bytes(), 0);
+        let stored = zip.local_file_at(109).unwrap();
+        assert!(stored.is_streamed());
+        assert_eq!(stored.file_data(), b"This file has PK\x07\x08 in it\n");
+        assert_eq!(stored.compressed_size(), 25);
+        if cfg!(feature = "deflate") {
+            assert!(zip.verify(true).iter().all(|i| i.is_ok()));
+        }
+
+        // Data descriptor without a signature
+        let item = Item {
+            name: "a.txt",
+            flags: 1 << 3,
+            crc32: 0xd87f7e0c,
+            data: b"test",
+            streamed: true,
+            descriptor: Some([0xd87f7e0c, 4, 4]),
+            unsigned_descriptor: true,
+            ..Default::default()
+        };
+        let zip = process_bytes(build_zip(&[item])).unwrap();
+
+        let local = zip.local_file_at(0).unwrap();
+        assert_eq!(local.file_data(), b"test");
+        assert_eq!(local.crc32(), 0xd87f7e0c);
+        assert_eq!(local.record_size(), 35 + 4 + 12);
+        assert_eq!(zip.verify(false)[0].status(), "OK");
+    }
+
+    // Strict mode
+
+    #[test]
+    fn zip_process_strict_test() {
+        let f = File::open("../exercise.zip").unwrap();
+        let mut r = BufReader::new(f);
+        let zip = Zip::process_strict(&mut r).unwrap();
+        assert_eq!(zip.summary().unwrap(), SUMMARY);
+        assert_eq!(zip.nonconformances(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn zip_from_strict_test() {
+        let zip = Zip::from_strict("../exercise.zip").unwrap();
+        assert_eq!(zip.summary().unwrap(), SUMMARY);
+    }
+
+    #[test]
+    fn zip_reader_test() {
+        let zip = Zip::reader().open("../exercise.zip").unwrap();
+        assert_eq!(zip.summary().unwrap(), SUMMARY);
+        let zip = Zip::reader()
+            .encoding(Encoding::Cp437)
+            .strict(true)
+            .open("../exercise.zip")
+            .unwrap();
+        assert_eq!(zip.encoding(), Encoding::Cp437);
+        assert_eq!(zip.summary().unwrap(), SUMMARY);
+
+        let mut bytes = build_zip(Layout {
+            prefix: &[0xff; 100],
+            items: &[Item {
+                name: "a.txt",
+                data: b"test",
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        bytes.extend(b"garbage");
+        let reader = Zip::reader().offset(100);
+
+        let zip = reader
+            .process(&mut BufReader::new(Cursor::new(bytes.clone())))
+            .unwrap();
+        assert_eq!(zip.base_offset(), 100);
+        assert_eq!(zip.trailing_bytes(), 7);
+        assert_eq!(
+            reader
+                .strict(true)
+                .process(&mut BufReader::new(Cursor::new(bytes)))
+                .unwrap_err(),
+            String::from("7 bytes of trailing data"),
+        );
+
+        let mut r = BufReader::new(Cursor::new(b"damaged beyond repair".to_vec()));
+        let zip = Zip::reader().lenient(true).process(&mut r).unwrap();
+        assert_eq!(zip.entries().len(), 0);
+        assert_eq!(zip.truncated(), Some("Invalid signature: `64616d61`"));
+
+        let bytes = build_zip(&[Item {
+            name: "a.txt",
+            data: b"test",
+            ..Default::default()
+        }]);
+        let zip = Zip::reader()
+            .max_alloc(5)
+            .process(&mut BufReader::new(Cursor::new(bytes.clone())))
+            .unwrap();
+        assert_eq!(zip.names(), vec!["a.txt"]);
+        let e = "Local file header (entry 0) at offset 0: file_name_length (5) exceeds the \
+            allocation limit of 4 bytes";
+        assert_eq!(
+            Zip::reader()
+                .max_alloc(4)
+                .process(&mut BufReader::new(Cursor::new(bytes.clone())))
+                .unwrap_err(),
+            e,
+        );
+        let zip = Zip::reader()
+            .max_alloc(4)
+            .lenient(true)
+            .process(&mut BufReader::new(Cursor::new(bytes)))
+            .unwrap();
+        assert_eq!(zip.entries().len(), 0);
+        assert_eq!(zip.truncated(), Some(e));
+
+        // The file data of a streamed local file does not declare its size, so the scan for its
+        // end is limited instead
+        let data = [b'a'; 100];
+        let bytes = build_zip(&[Item {
+            name: "a.txt",
+            flags: 1 << 3,
+            crc32: 0xaf707a64,
+            data: &data,
+            streamed: true,
+            descriptor: Some([0xaf707a64, 100, 100]),
+            ..Default::default()
+        }]);
+        let zip = Zip::reader()
+            .max_alloc(100)
+            .process(&mut BufReader::new(Cursor::new(bytes.clone())))
+            .unwrap();
+        assert_eq!(zip.local_file_at(0).unwrap().file_data(), data);
+        let e = "Streamed file data at offset 35 exceeds the allocation limit of 99 bytes";
+        assert_eq!(
+            Zip::reader()
+                .max_alloc(99)
+                .process(&mut BufReader::new(Cursor::new(bytes.clone())))
+                .unwrap_err(),
+            e,
+        );
+        let zip = Zip::reader()
+            .max_alloc(99)
+            .lenient(true)
+            .process(&mut BufReader::new(Cursor::new(bytes)))
+            .unwrap();
+        assert_eq!(zip.entries().len(), 0);
+        assert_eq!(zip.truncated(), Some(e));
+    }
+
+    #[test]
+    fn zip_trailing_data_test() {
+        let mut bytes = build_zip(&[Item {
+            name: "a.txt",
+            data: b"test",
+            ..Default::default()
+        }]);
+        bytes.extend(b"garbage");
+        let zip = process_bytes(bytes.clone()).unwrap();
+        assert_eq!(zip.trailing_bytes(), 7);
+        let mut r = BufReader::new(Cursor::new(bytes));
+        assert_eq!(
+            Zip::process_strict(&mut r).unwrap_err(),
+            String::from("7 bytes of trailing data"),
+        );
+    }
+
+    #[test]
+    fn zip_missing_eocd_test() {
+        let item = Item {
+            name: "a.txt",
+            data: b"test",
+            ..Default::default()
+        };
+        let zip = process_bytes(build_zip(Layout {
+            items: &[item],
+            omit_eocd: true,
+            ..Default::default()
+        }))
+        .unwrap();
+        assert_eq!(
+            zip.strict().unwrap_err(),
+            String::from("Missing end of central directory record"),
+        );
+    }
+
+    #[test]
+    fn zip_count_mismatch_test() {
+        let item = Item {
+            name: "a.txt",
+            data: b"test",
+            ..Default::default()
+        };
+        let zip = process_bytes(build_zip(Layout {
+            items: &[item],
+            entries: Some(2),
+            cd_size: Some(52),
+            ..Default::default()
+        }))
+        .unwrap();
+        assert_eq!(
+            zip.nonconformances(),
+            vec![
+                String::from(
+                    "End of central directory record disk entries (2) does not match the number \
+                    of central directory file headers (1)",
+                ),
+                String::from(
+                    "End of central directory record total entries (2) does not match the \
+                    number of central directory file headers (1)",
+                ),
+                String::from(
+                    "End of central directory record central directory size (52) does not \
+                    match the actual size (51)",
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn zip_lossy_name_test() {
+        let item = Item {
+            name: "a\u{e9}.txt",
+            data: b"test",
+            ..Default::default()
+        };
+        let mut bytes = build_zip(&[item]);
+        // Replace the UTF-8 encoded `é` (`c3 a9`) with its Latin-1 encoding (`e9`) in both
+        // headers, keeping the lengths by replacing the second byte with a `_`
+        for i in 0..bytes.len() - 1 {
+            if bytes[i..i + 2] == [0xc3, 0xa9] {
+                bytes[i] = 0xe9;
+                bytes[i + 1] = b'_';
+            }
+        }
+        let zip = process_bytes(bytes).unwrap();
+        assert_eq!(
+            zip.summary().unwrap(),
+            "a\u{398}_.txt\tfalse\t4\t1980-01-01T00:00:00\t\n",
+        );
+        assert_eq!(
+            zip.strict().unwrap_err(),
+            String::from("File name at offset 0 is not valid UTF-8: `61e95f2e747874`"),
+        );
+        let zip = zip.with_encoding(Encoding::Utf8);
+        assert_eq!(
+            zip.summary().unwrap(),
+            "a\u{fffd}_.txt\tfalse\t4\t1980-01-01T00:00:00\t\n",
+        );
+        assert_eq!(
+            zip.strict().unwrap_err(),
+            String::from("File name at offset 0 cannot be decoded without loss: `61e95f2e747874`"),
+        );
+
+        // CP437 decodes any bytes without loss
+        let zip = zip.with_encoding(Encoding::Cp437);
+        assert_eq!(zip.names(), ["a\u{398}_.txt"]);
+        assert_eq!(zip.strict(), Ok(()));
+    }
+
+    #[test]
+    fn cp437_round_trip_test() {
+        // Every byte decodes to a different character, so the bytes can be recovered
+        let bytes = (0..=255).collect::<Vec<u8>>();
+        let decoded = cp437(&bytes);
+        let chars = decoded.chars().collect::<BTreeSet<_>>();
+        assert_eq!(chars.len(), 256);
+        assert_eq!(decoded.chars().count(), 256);
+    }
+
+    // Warnings
+
+    #[test]
+    fn zip_warnings_test() {
+        assert_eq!(Zip::from("../exercise.zip").unwrap().warnings(), vec![]);
+
+        // Written by Python's `zipfile`, then the file name `X.txt` patched to `\x81.txt`
+        // (`ü.txt` in CP437)
+        let zip = Zip::from("../warnings.zip").unwrap();
+        let duplicate = Warning::DuplicateName {
+            name: String::from("a.txt"),
+            count: 2,
+        };
+        let ratio = Warning::SuspiciousRatio {
+            name: String::from("zeros.bin"),
+            compressed_size: 27,
+            uncompressed_size: 10000,
+        };
+        assert_eq!(zip.warnings(), vec![duplicate.clone(), ratio.clone()]);
+        assert_eq!(duplicate.to_string(), "File name `a.txt` appears 2 times");
+        assert_eq!(
+            ratio.to_string(),
+            "Item `zeros.bin` expands from 27 to 10000 bytes, more than 100 times",
+        );
+        let lossy = Warning::LossyName {
+            offset: 236,
+            name: String::from("812e747874"),
+        };
+        assert_eq!(
+            zip.with_encoding(Encoding::Utf8).warnings(),
+            vec![lossy.clone(), duplicate, ratio],
+        );
+        assert_eq!(
+            lossy.to_string(),
+            "File name `812e747874` (hex) at offset 236 cannot be decoded without loss",
+        );
+    }
+
+    #[test]
+    fn zip_check_data_descriptor_test() {
+        // The flag decides, so the next record is misread and only a lenient parse keeps the
+        // local file
+        let item = Item {
+            name: "a.txt",
+            flags: 1 << 3,
+            data: b"test",
+            ..Default::default()
+        };
+        let bytes = build_zip(&[item]);
+        let missing = CheckFinding::MissingDataDescriptor {
+            offset: 0,
+            name: String::from("a.txt"),
+        };
+        let e = format!("Invalid signature: `00002100` ({missing})");
+        assert_eq!(process_bytes(bytes.clone()).unwrap_err(), e);
+        let zip = Zip::process_lenient(&mut BufReader::new(Cursor::new(bytes))).unwrap();
+        assert_eq!(zip.truncated(), Some(e.as_str()));
+        assert_eq!(zip.check(), vec![missing.clone()]);
+        assert_eq!(
+            missing.to_string(),
+            "Local file `a.txt` at offset 0 sets the data descriptor flag but has no data \
+            descriptor",
+        );
+
+        let item = Item {
+            name: "a.txt",
+            crc32: 0xd87f7e0c,
+            data: b"test",
+            descriptor: Some([0xd87f7e0c, 4, 4]),
+            ..Default::default()
+        };
+        let bytes = build_zip(&[item]);
+        let unflagged = CheckFinding::UnflaggedDataDescriptor {
+            offset: 0,
+            name: String::from("a.txt"),
+        };
+        let e = format!("Invalid signature: `504b0708` ({unflagged})");
+        assert_eq!(process_bytes(bytes.clone()).unwrap_err(), e);
+        let zip = Zip::process_lenient(&mut BufReader::new(Cursor::new(bytes))).unwrap();
+        assert_eq!(zip.check(), vec![unflagged.clone()]);
+        assert_eq!(
+            unflagged.to_string(),
+            "Local file `a.txt` at offset 0 has a data descriptor but does not set the data \
+            descriptor flag",
+        );
+    }
+
+    #[test]
+    fn zip_warnings_entry_count_mismatch_test() {
+        let item = Item {
+            name: "a.txt",
+            data: b"test",
+            ..Default::default()
+        };
+        let bytes = build_zip(Layout {
+            items: &[item],
+            entries: Some(2),
+            ..Default::default()
+        });
+        let warnings = process_bytes(bytes).unwrap().warnings();
+        assert_eq!(
+            warnings,
+            vec![Warning::EntryCountMismatch {
+                declared: 2,
+                actual: 1,
+            }],
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "End of central directory record declares 2 entries but there are 1 central directory \
+            file headers",
+        );
+    }
+
+    // Encodings
+
+    #[test]
+    fn encoding_test() {
+        let bytes = b"a\xc3\xa9\x82";
+        assert_eq!(Encoding::Auto.decode(bytes, 0), "a\u{251c}\u{2310}\u{e9}");
+        assert_eq!(Encoding::Auto.decode(b"a\xc3\xa9", 0), "a\u{e9}");
+        assert_eq!(Encoding::Cp437.decode(b"a\xc3\xa9", 0), "a\u{251c}\u{2310}");
+        assert_eq!(Encoding::Cp437.decode(b"a\xc3\xa9", 1 << 11), "a\u{e9}");
+        assert_eq!(Encoding::Utf8.decode(bytes, 0), "a\u{e9}\u{fffd}");
+        assert_eq!("UTF-8".parse::<Encoding>(), Ok(Encoding::Utf8));
+        assert_eq!("cp437".parse::<Encoding>(), Ok(Encoding::Cp437));
+    }
+
+    #[test]
+    fn record_encoding_test() {
+        let item = Item {
+            name: "a\u{e9}.txt",
+            ..Default::default()
+        };
+        let mut bytes = build_zip(&[item]);
+        for n in bytes
+            .windows(4)
+            .enumerate()
+            .filter(|(_, w)| *w == b"PK\x01\x02")
+            .map(|(n, _)| n)
+            .collect::<Vec<_>>()
+        {
+            // Replace the second byte of `é` so the file name is not valid UTF-8
+            bytes[n + 48] = b'x';
+        }
+        let zip = process_bytes(bytes).unwrap();
+        let header = zip.central_directory().next().unwrap();
+        let options = SummaryOptions::default();
+        assert_eq!(
+            header.summary(&options),
+            header.summary_with_encoding(Encoding::Auto, &options),
+        );
+        assert!(header.summary(&options).starts_with("a\u{251c}x.txt\t"));
+        assert!(header
+            .summary_with_encoding(Encoding::Utf8, &options)
+            .starts_with("a\u{fffd}x.txt\t"));
+        assert!(header.verbose().contains("(\"a\u{251c}x.txt\")"));
+        assert!(header
+            .verbose_with_encoding(Encoding::Utf8)
+            .contains("(\"a\u{fffd}x.txt\")"));
+    }
+
+    #[cfg(feature = "encoding")]
+    #[test]
+    fn encoding_legacy_test() {
+        let encoding = "shift_jis".parse::<Encoding>().unwrap();
+        assert_eq!(encoding, Encoding::Legacy(encoding_rs::SHIFT_JIS));
+        assert_eq!(encoding.decode(b"\x93\xfa\x96\x7b", 0), "\u{65e5}\u{672c}");
+        assert_eq!(
+            "nonexistent".parse::<Encoding>().unwrap_err(),
+            "Unknown encoding: `nonexistent` (expected `auto`, `utf-8`, `cp437`, or an encoding \
+            label such as `shift_jis`, `gbk`, or `euc-kr`)",
+        );
+    }
+
+    // Extra fields
+
+    #[test]
+    fn extra_fields_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let header = zip.find("folder00/").unwrap();
+        assert_eq!(
+            zip.local_file_for(header).unwrap().extra_fields(),
+            Ok(vec![]),
+        );
+        assert_eq!(
+            header.extra_fields().unwrap(),
+            vec![ExtraField {
+                tag: 0x000a,
+                data: hex::decode(
+                    "00000000010018005bef1d52986bd8015bef1d52986bd801c87ec7b5e87ad601",
+                )
+                .unwrap(),
+            }],
+        );
+        assert_eq!(
+            extra_fields_verbose(header.extra_field()).unwrap(),
+            "\
+tag = 0x000a (NTFS)
+size = 0x0020 (32)
+data = \"00000000010018005bef1d52986bd8015bef1d52986bd801c87ec7b5e87ad601\"
+mtime = 2022-05-19T15:51:36.4324187Z
+atime = 2022-05-19T15:51:36.4324187Z
+ctime = 2020-08-25T14:04:55.4759880Z
+\
+            ",
+        );
+    }
+
+    #[test]
+    fn extra_fields_truncated_test() {
+        assert_eq!(
+            extra_fields(&hex::decode("0a0020000000").unwrap()).unwrap_err(),
+            String::from("Truncated extra field 0x000a: size is 32 but only 2 bytes remain"),
+        );
+    }
+
+    #[test]
+    fn extra_field_interpret_test() {
+        let aes = ExtraField {
+            tag: 0x9901,
+            data: hex::decode("02004145030800").unwrap(),
+        };
+        assert_eq!(
+            aes.interpret(),
+            vec![
+                ("vendor_version", String::from("AE-2")),
+                ("vendor_id", String::from("\"AE\"")),
+                ("strength", String::from("3 (256-bit)")),
+                ("compression", String::from("8")),
+            ],
+        );
+        let unix = ExtraField {
+            tag: 0x7875,
+            data: hex::decode("0104e803000004e8030000").unwrap(),
+        };
+        assert_eq!(
+            unix.interpret(),
+            vec![
+                ("version", String::from("1")),
+                ("uid", String::from("1000")),
+                ("gid", String::from("1000")),
+            ],
+        );
+    }
+
+    // Extract
+
+    #[test]
+    fn zip_extract_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        assert_eq!(zip.extract("folder00/folder00-00/test00-00-00.txt").unwrap(), b"test");
+        assert_eq!(
+            zip.extract("nonexistent").unwrap_err(),
+            String::from("Item not found: `nonexistent`"),
+        );
+        assert_eq!(
+            zip.extract("folder00/").unwrap_err(),
+            String::from("Item is a folder: `folder00/`"),
+        );
+        let mut w = vec![];
+        zip.extract_to("test00.txt", &mut w).unwrap();
+        assert_eq!(w, b"test");
+
+        // The CRC-32 of the decompressed data must match
+        let item = Item {
+            name: "a.txt",
+            crc32: 0xd87f7e0d,
+            data: b"test",
+            ..Default::default()
+        };
+        let zip = process_bytes(build_zip(&[item])).unwrap();
+        assert_eq!(
+            zip.extract("a.txt").unwrap_err(),
+            "CRC-32 mismatch for `a.txt`: expected 0xd87f7e0d, computed 0xd87f7e0c",
+        );
+        assert!(zip.extract_to("a.txt", &mut vec![]).is_err());
+        assert!(zip.sbom(false).contains(r#""sha256":null"#));
+    }
+
+    #[cfg(feature = "deflate")]
+    #[test]
+    fn zip_nested_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let nested = zip.nested("folder01/exercise.zip").unwrap();
+        let want = SUMMARY
+            .lines()
+            .filter(|x| !x.starts_with("folder01/exercise.zip\t"))
+            .map(|x| format!("{x}\n"))
+            .collect::<String>();
+        assert_eq!(nested.summary().unwrap(), want);
+        assert_eq!(
+            zip.nested("folder00/folder00-00/test00-00-00.txt").unwrap_err(),
+            String::from("Invalid signature: `74657374`"),
+        );
+        let header = zip.find("folder01/exercise.zip").unwrap();
+        let size = header.uncompressed_size();
+        let reader = Zip::reader().max_alloc(100).strict(true);
+        assert_eq!(
+            zip.nested_with("folder01/exercise.zip", &reader)
+                .unwrap_err(),
+            format!(
+                "Nested zip file `folder01/exercise.zip`: uncompressed size ({size}) exceeds the \
+                allocation limit of 100 bytes",
+            ),
+        );
+        let reader = Zip::reader().max_alloc(1 << 20).strict(true);
+        let nested = zip.nested_with("folder01/exercise.zip", &reader).unwrap();
+        assert_eq!(nested.summary().unwrap(), want);
+    }
+
+    #[test]
+    fn zip_extract_plan_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let plan = zip.extract_plan(Path::new("out"));
+        assert_eq!(plan.len(), 13);
+        assert_eq!(plan[0], (String::from("folder00/"), PathBuf::from("out/folder00")));
+        assert_eq!(
+            plan[2].1,
+            PathBuf::from("out/folder00/folder00-00/test00-00-00.txt"),
+        );
+        assert!(zip.unsafe_paths().is_empty());
+
+        let items = [
+            Item {
+                name: "../evil.txt",
+                ..Default::default()
+            },
+            Item {
+                name: "ok.txt",
+                ..Default::default()
+            },
+        ];
+        let zip = process_bytes(build_zip(&items)).unwrap();
+        assert_eq!(
+            zip.extract_plan(Path::new("out")),
+            vec![(String::from("ok.txt"), PathBuf::from("out/ok.txt"))],
+        );
+        assert_eq!(
+            zip.unsafe_paths(),
+            vec![(
+                String::from("../evil.txt"),
+                String::from("Path traversal: `../evil.txt`"),
+            )],
+        );
+    }
+
+    #[test]
+    fn zip_extract_all_test() {
+        let dest = std::env::temp_dir().join(format!("zp-lib-{}-extract", std::process::id()));
+        let items = [
+            Item {
+                name: "a/b.txt",
+                crc32: 0xd87f7e0c,
+                data: b"test",
+                ..Default::default()
+            },
+            Item {
+                name: "c/",
+                ..Default::default()
+            },
+        ];
+        let zip = process_bytes(build_zip(&items)).unwrap();
+        let plan = zip.extract_all(&dest);
+        let data = std::fs::read(dest.join("a/b.txt"));
+        let folder = dest.join("c").is_dir();
+        std::fs::remove_dir_all(&dest).unwrap();
+        assert_eq!(plan.unwrap(), zip.extract_plan(&dest));
+        assert_eq!(data.unwrap(), b"test");
+        assert!(folder);
+
+        // Nothing is written if there is an unsafe path or a collision
+        let items = [
+            Item {
+                name: "ok.txt",
+                ..Default::default()
+            },
+            Item {
+                name: "../evil.txt",
+                ..Default::default()
+            },
+        ];
+        let zip = process_bytes(build_zip(&items)).unwrap();
+        assert_eq!(
+            zip.extract_all(&dest).unwrap_err(),
+            "Path traversal: `../evil.txt`",
+        );
+        let items = [
+            Item {
+                name: "a.txt",
+                ..Default::default()
+            },
+            Item {
+                name: "./a.txt",
+                ..Default::default()
+            },
+        ];
+        let zip = process_bytes(build_zip(&items)).unwrap();
+        assert_eq!(
+            zip.extract_all(Path::new("out")).unwrap_err(),
+            "Path collision: `./a.txt` -> `out/a.txt`",
+        );
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn supported_compression_methods_test() {
+        let methods = supported_compression_methods();
+        assert!(methods.contains(&0));
+        assert_eq!(methods.contains(&8), cfg!(feature = "deflate"));
+        assert_eq!(
+            decompress(12, b"", 0).unwrap_err(),
+            String::from("Unsupported compression method: 12"),
+        );
+        assert_eq!(compression_name(9), "deflate64");
+        assert_eq!(
+            decompress(9, b"\x03\x00", 0).unwrap_err(),
+            String::from("Deflate64 is not supported"),
+        );
+    }
+
+    #[test]
+    fn is_safe_path_test() {
+        assert_eq!(is_safe_path("a/b/c"), Ok(PathBuf::from("a").join("b").join("c")));
+        assert_eq!(is_safe_path("./a//b/"), Ok(PathBuf::from("a").join("b")));
+        assert_eq!(is_safe_path("a\\b"), Ok(PathBuf::from("a").join("b")));
+        for (name, want) in [
+            ("../x", "Path traversal: `../x`"),
+            ("a/../../b", "Path traversal: `a/../../b`"),
+            ("a\\..\\b", "Path traversal: `a\\..\\b`"),
+            ("/etc/passwd", "Absolute path: `/etc/passwd`"),
+            ("\\\\server\\share\\x", "Absolute path: `\\\\server\\share\\x`"),
+            ("C:\\x", "Drive letter prefix: `C:\\x`"),
+            ("c:x", "Drive letter prefix: `c:x`"),
+            ("a/C:/x", "Colon in path: `a/C:/x`"),
+            ("a/C:x", "Colon in path: `a/C:x`"),
+            ("a\\b:stream", "Colon in path: `a\\b:stream`"),
+            ("./", "Empty path: `./`"),
+            ("", "Empty path: ``"),
+        ] {
+            assert_eq!(is_safe_path(name).unwrap_err(), want);
+        }
+    }
+
+    // Verify
+
+    #[test]
+    fn zip_verify_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let verifications = zip.verify(false);
+        assert_eq!(verifications.len(), 13);
+        assert!(verifications.iter().all(|i| !i.is_bad()));
+        let skipped = verifications
+            .iter()
+            .filter(|i| !i.is_ok())
+            .map(|i| i.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            skipped,
+            vec![
+                "folder00/folder00-00/test00-00-01.txt",
+                "folder00/test00-00.txt",
+                "folder01/exercise.zip",
+                "folder01/test01-00.txt",
+            ],
+        );
+        assert_eq!(
+            verifications[3].to_string(),
+            "folder00/folder00-00/test00-00-01.txt: - (compressed; use deep verification)",
+        );
+    }
+
+    #[cfg(feature = "deflate")]
+    #[test]
+    fn zip_verify_deep_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        assert!(zip.verify(true).iter().all(|i| i.is_ok()));
+    }
+
+    #[cfg(feature = "deflate")]
+    #[test]
+    fn zip_decompress_declared_size_test() {
+        // 100 `a`s deflate to 6 bytes, but the sizes claim 6 uncompressed bytes
+        let data = b"KL\xa4=\x00\x00";
+        let e = "Decompressed data expands past its declared size of 6 bytes";
+        assert_eq!(decompress(8, data, 6).unwrap_err(), e);
+        assert_eq!(decompress(8, data, 100).unwrap(), [b'a'; 100]);
+        let item = Item {
+            name: "a.txt",
+            compression: 8,
+            crc32: 0xaf707a64,
+            data,
+            ..Default::default()
+        };
+        let zip = process_bytes(build_zip(&[item])).unwrap();
+        assert_eq!(zip.extract("a.txt").unwrap_err(), e);
+        assert_eq!(zip.verify(true)[0].computed, Err(String::from(e)));
+    }
+
+    #[test]
+    fn zip_summary_rows_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let rows = zip.summary_rows(&SummaryOptions::default());
+        assert_eq!(rows, SUMMARY.lines().collect::<Vec<_>>());
+        let options = SummaryOptions {
+            verify: true,
+            comment: true,
+            separator: Some(String::from("\0")),
+            filter: vec![String::from("test00.txt")],
+            ..Default::default()
+        };
+        assert_eq!(
+            zip.summary_rows(&options),
+            ["test00.txt\tfalse\t4\t2020-08-25T09:05:38\tA top level file\tOK"],
+        );
+    }
+
+    #[test]
+    fn zip_summary_verify_test() {
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let mut options = SummaryOptions {
+            verify: true,
+            ..Default::default()
+        };
+        let compressed = [
+            "folder00/folder00-00/test00-00-01.txt",
+            "folder00/test00-00.txt",
+            "folder01/exercise.zip",
+            "folder01/test01-00.txt",
+        ];
+        let want = SUMMARY
+            .lines()
+            .map(|x| {
+                let name = x.split('\t').next().unwrap();
+                let status = if compressed.contains(&name) { "-" } else { "OK" };
+                format!("{x}\t{status}\n")
+            })
+            .collect::<String>();
+        assert_eq!(zip.summary_with(&options).unwrap(), want);
+        if cfg!(feature = "deflate") {
+            options.deep = true;
+            let want = SUMMARY
+                .lines()
+                .map(|x| format!("{x}\tOK\n"))
+                .collect::<String>();
+            assert_eq!(zip.summary_with(&options).unwrap(), want);
+        }
+    }
+
+    #[test]
+    fn zip_verify_bad_test() {
+        let item = Item {
+            name: "a.txt",
+            crc32: 0x12345678,
+            data: b"test",
+            ..Default::default()
+        };
+        let zip = process_bytes(build_zip(&[item])).unwrap();
+        let verifications = zip.verify(false);
+        assert!(verifications[0].is_bad());
+        assert_eq!(verifications[0].status(), "BAD");
+        assert_eq!(
+            verifications[0].to_string(),
+            "a.txt: BAD (expected 0x12345678, computed 0xd87f7e0c)",
+        );
+    }
+
+    // Conversion functions
+
+    #[test]
+    fn sniff_format_test() {
+        assert_eq!(sniff_format(b"\x1f\x8b\x08\x00"), Some("gzip"));
+        assert_eq!(sniff_format(b"PK\x03\x04"), None);
+        assert_eq!(sniff_format(b""), None);
+    }
+
+    #[test]
+    fn parse_crc32_test() {
+        assert_eq!(parse_crc32("d87f7e0c"), Ok(0xd87f7e0c));
+        assert_eq!(parse_crc32("0xD87F7E0C"), Ok(0xd87f7e0c));
+        assert_eq!(parse_crc32("0"), Ok(0));
+        for s in ["", "0x", "+1", "xyz", "123456789"] {
+            assert_eq!(parse_crc32(s).unwrap_err(), format!("Invalid CRC-32: `{s}`"));
+        }
+    }
+
+    #[test]
+    fn hexdump_test() {
+        assert_eq!(
+            hexdump(b"PK\x03\x04 test\n", 0x10),
+            "00000010  50 4b 03 04 20 74 65 73  74 0a                    |PK.. test.|\n",
+        );
+        let bytes = std::fs::read("../exercise.zip").unwrap();
+        assert_eq!(
+            hexdump(&bytes[..39], 0),
+            "\
+00000000  50 4b 03 04 14 00 00 00  00 00 73 56 b3 54 00 00  |PK........sV.T..|
+00000010  00 00 00 00 00 00 00 00  00 00 09 00 00 00 66 6f  |..............fo|
+00000020  6c 64 65 72 30 30 2f                              |lder00/|
+",
+        );
+        assert_eq!(hexdump(b"", 0), "");
+    }
+
+    #[test]
+    fn glob_match_test() {
+        assert!(glob_match("*.txt", "test00.txt"));
+        assert!(!glob_match("*.txt", "folder00/test00-00.txt"));
+        assert!(glob_match("**/*.txt", "test00.txt"));
+        assert!(glob_match("**/*.txt", "folder00/a/b.txt"));
+        assert!(glob_match("node_modules/**", "node_modules/"));
+        assert!(glob_match("node_modules/**", "node_modules/a/b.js"));
+        assert!(!glob_match("node_modules/**", "node_modules"));
+        assert!(glob_match("test0?.txt", "test01.txt"));
+        assert!(!glob_match("test0?.txt", "test0/.txt"));
+        assert!(glob_match("a/**/b", "a/b"));
+        assert!(!glob_match("a", "ab"));
+
+        // Many stars take no more than one pass per pattern character
+        let name = "a".repeat(1000);
+        assert!(!glob_match(&format!("{}b", "*a".repeat(20)), &name));
+        assert!(glob_match(&format!("{}a", "**a".repeat(20)), &name));
+    }
+
+    #[test]
+    fn parse_escapes_test() {
+        assert_eq!(parse_escapes("\\0"), Ok(String::from("\0")));
+        assert_eq!(parse_escapes(","), Ok(String::from(",")));
+        assert_eq!(parse_escapes("\\t\\n\\r\\\\"), Ok(String::from("\t\n\r\\")));
+        assert_eq!(
+            parse_escapes("\\x").unwrap_err(),
+            "Invalid escape sequence `\\x` in `\\x`",
+        );
+        assert_eq!(
+            parse_escapes("a\\").unwrap_err(),
+            "Incomplete escape sequence in `a\\`",
+        );
+    }
+
+    #[test]
+    fn parse_size_test() {
+        assert_eq!(parse_size("512"), Ok(512));
+        assert_eq!(parse_size("64K"), Ok(64 << 10));
+        assert_eq!(parse_size("1M"), Ok(1 << 20));
+        assert_eq!(parse_size("2gib"), Ok(2 << 30));
+        assert_eq!(parse_size("1TB"), Ok(1 << 40));
+        assert_eq!(
+            parse_size("1.5M").unwrap_err(),
+            String::from("Invalid size: `1.5M`"),
+        );
+    }
+
+    #[test]
+    fn crc32_test() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"test"), 0xd87f7e0c);
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+    }
+
+    #[cfg(feature = "hash")]
+    #[test]
+    fn sha256_test() {
+        assert_eq!(
+            sha256(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        );
+        assert_eq!(
+            sha256(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        );
+        // Two blocks after padding
+        assert_eq!(
+            sha256(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+        );
+    }
+
+    #[test]
+    fn unix_datetime_test() {
+        assert_eq!(unix_datetime(0), "1970-01-01T00:00:00Z");
+        assert_eq!(unix_datetime(951782400), "2000-02-29T00:00:00Z");
+        assert_eq!(unix_datetime(-1), "1969-12-31T23:59:59Z");
+    }
+
+    #[test]
+    fn dos_datetime_utc_test() {
+        assert_eq!(dos_datetime_utc(0x0021, 0, 0), "1980-01-01T00:00:00Z");
+        assert_eq!(dos_datetime_utc(0x0021, 0, 60), "1979-12-31T23:00:00Z");
+        for (offset, want) in [
+            (0, "2022-05-19T10:51:38Z"),
+            (120, "2022-05-19T08:51:38Z"),
+            (-780, "2022-05-19T23:51:38Z"),
+        ] {
+            assert_eq!(dos_datetime_utc(0x54b3, 0x5673, offset), want);
+        }
+        let zip = Zip::from("../exercise.zip").unwrap();
+        let options = SummaryOptions {
+            utc_offset: Some(-300),
+            ..Default::default()
+        };
+        let summary = zip.summary_with(&options).unwrap();
+        assert_eq!(
+            summary.lines().next().unwrap(),
+            "folder00/\ttrue\t0\t2022-05-19T15:51:38Z\t",
+        );
+        assert_eq!(summary.lines().count(), SUMMARY.lines().count());
+    }
+
+    #[test]
+    fn parse_utc_offset_test() {
+        for (s, want) in [
+            ("Z", 0),
+            ("utc", 0),
+            ("+00:00", 0),
+            ("+02:00", 120),
+            ("-05:00", -300),
+            ("+0530", 330),
+            ("-9", -540),
+            ("+23:59", 1439),
+        ] {
+            assert_eq!(parse_utc_offset(s), Ok(want));
+        }
+        for s in ["", "02:00", "+", "+24:00", "+02:60", "+2:0", "+123", "+é1"] {
+            let want = format!("Invalid UTC offset: `{s}`");
+            assert_eq!(parse_utc_offset(s).unwrap_err(), want);
+        }
+    }
 }
@@ -0,0 +1,123 @@
+use crate::*;
+
+impl Zip {
+    /// Find the item named `name` by reading only the central directory, for inspecting one file
+    /// in a large zip file
+    ///
+    /// Seeks to the end of central directory record (within the last 64 KiB), then reads the
+    /// central directory file headers from `cd_offset` until one has the name (decoded with
+    /// [`Encoding::Auto`]), and the fixed-size part of its local file header; the rest of the
+    /// central directory and all file data are skipped.
+    /// Requires a seekable source, since the central directory is at the end of the zip file.
+    /// Offsets are relative to the reader's current position, as in [`Zip::process`], which is
+    /// restored before returning.
+    ///
+    /// Returns the central directory file header with the absolute offset and length of the
+    /// compressed file data (see [`Zip::data_ranges`]), or `None` if no item has the name.
+    pub fn locate<R>(
+        r: &mut R,
+        name: &str,
+    ) -> Result<Option<(CentralDirectoryFileHeader, u64, u64)>, String>
+    where
+        R: Read + Seek,
+    {
+        let io = |e: std::io::Error| e.to_string();
+        let start = r.stream_position().map_err(io)?;
+        let result = locate(r, start, name);
+        r.seek(SeekFrom::Start(start)).map_err(io)?;
+        result
+    }
+}
+
+impl CentralDirectoryFileHeader {
+    /// Absolute offset of the file data in `r`, read from the local file header at `lfh_offset`
+    ///
+    /// The local file header's file name and extra field lengths are used rather than the
+    /// central directory file header's, since the extra fields often differ (for example, the
+    /// extended timestamp is shorter in the central directory).
+    /// Offsets are from the start of `r`, so `r` must begin with the zip file (use
+    /// [`Zip::locate`] or [`Zip::data_ranges`] for a zip file with a prefix).
+    /// Reads only the fixed-size part of the local file header.
+    pub fn data_offset<R>(&self, r: &mut R) -> Result<u64, String>
+    where
+        R: Read + Seek,
+    {
+        read_data_offset(r, self.lfh_offset() as u64)
+    }
+}
+
+/// Read the fixed-size part of the local file header at absolute `offset` and return the
+/// absolute offset of its file data
+fn read_data_offset<R>(r: &mut R, offset: u64) -> Result<u64, String>
+where
+    R: Read + Seek,
+{
+    let io = |e: std::io::Error| e.to_string();
+    r.seek(SeekFrom::Start(offset)).map_err(io)?;
+    let mut b = [0; 30];
+    r.read_exact(&mut b).map_err(io)?;
+    if !b.starts_with(b"PK\x03\x04") {
+        return Err(format!("Invalid signature: `{}`", hex::encode(&b[..4])));
+    }
+    let (file_name_length, extra_field_length) = local_header_lengths(&b).unwrap_or_default();
+    Ok(offset + local_data_offset(file_name_length, extra_field_length))
+}
+
+/// Scan the central directory for [`Zip::locate`]
+fn locate<R>(
+    r: &mut R,
+    start: u64,
+    name: &str,
+) -> Result<Option<(CentralDirectoryFileHeader, u64, u64)>, String>
+where
+    R: Read + Seek,
+{
+    let io = |e: std::io::Error| e.to_string();
+    let (tail_start, tail) = read_tail(r, start)?;
+    let n = find_end_of_central_directory(&tail)
+        .ok_or_else(|| String::from("End of central directory record not found"))?;
+    let u32_at = |i: usize| u32::from_le_bytes([tail[i], tail[i + 1], tail[i + 2], tail[i + 3]]);
+    let eocd_offset = tail_start + n as u64;
+    let cd_offset = start + u32_at(n + 16) as u64;
+    let cd_end = cd_offset + u32_at(n + 12) as u64;
+    if cd_end > eocd_offset {
+        return Err(format!(
+            "End of central directory record declares a central directory ending at offset \
+            {cd_end}, past the end of central directory record at offset {eocd_offset}",
+        ));
+    }
+
+    r.seek(SeekFrom::Start(cd_offset)).map_err(io)?;
+    let mut r = BufReader::new(r);
+    let mut pos = cd_offset;
+    while pos < cd_end {
+        check_signature(&mut r, b"PK\x01\x02")?;
+        let header = r
+            .read_le::<CentralDirectoryFileHeader>()
+            .map_err(|e| e.to_string())?;
+        pos += header.record_size();
+        if header.name(Encoding::Auto) != name {
+            continue;
+        }
+
+        let data = read_data_offset(&mut r, start + header.lfh_offset() as u64)?;
+        let length = header.compressed_size() as u64;
+        return Ok(Some((header, data, length)));
+    }
+    Ok(None)
+}
+
+/// Check the signature at the current position without consuming it
+fn check_signature<R>(r: &mut BufReader<R>, signature: &[u8; 4]) -> Result<(), String>
+where
+    R: Read + Seek,
+{
+    let io = |e: std::io::Error| e.to_string();
+    let mut b = [0; 4];
+    r.read_exact(&mut b).map_err(io)?;
+    r.seek_relative(-4).map_err(io)?;
+    if &b != signature {
+        return Err(format!("Invalid signature: `{}`", hex::encode(b)));
+    }
+    Ok(())
+}
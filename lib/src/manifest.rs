@@ -0,0 +1,121 @@
+use crate::*;
+
+/// Difference between a zip file and an expected manifest; see [`Zip::matches_manifest`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Mismatch {
+    /// An expected item is not in the zip file
+    Missing { name: String },
+
+    /// An item in the zip file is not in the manifest
+    Extra { name: String },
+
+    /// An item's CRC-32 and/or uncompressed size differ from the manifest
+    Differs {
+        name: String,
+        expected_crc32: u32,
+        crc32: u32,
+        expected_size: u64,
+        size: u64,
+    },
+}
+
+impl std::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Mismatch::Missing { name } => write!(f, "Missing `{name}`"),
+            Mismatch::Extra { name } => write!(f, "Extra `{name}`"),
+            Mismatch::Differs {
+                name,
+                expected_crc32,
+                crc32,
+                expected_size,
+                size,
+            } => write!(
+                f,
+                "Differs `{name}`: CRC-32 0x{crc32:08x} and size {size}, expected \
+                0x{expected_crc32:08x} and {expected_size}",
+            ),
+        }
+    }
+}
+
+impl Zip {
+    /// Compare the name, CRC-32, and uncompressed size of each central directory file header
+    /// with an expected manifest (see [`parse_manifest`]), e.g. to verify a reproducible build
+    ///
+    /// Reports the expected items that are missing or differ, in manifest order, then the items
+    /// that are not in the manifest, in central directory order; an empty list means the zip file
+    /// contains exactly the expected items.
+    /// Folders are items too.
+    /// If a name appears more than once, only its first occurrence is compared.
+    pub fn matches_manifest(&self, expected: &[(String, u32, u64)]) -> Vec<Mismatch> {
+        let mut actual = BTreeMap::new();
+        for i in self.central_directory() {
+            actual
+                .entry(i.name(self.encoding).into_owned())
+                .or_insert((i.crc32(), i.uncompressed_size() as u64));
+        }
+        let mut mismatches = vec![];
+        let mut seen = BTreeSet::new();
+        for (name, expected_crc32, expected_size) in expected {
+            if !seen.insert(name.as_str()) {
+                continue;
+            }
+            match actual.get(name) {
+                None => mismatches.push(Mismatch::Missing { name: name.clone() }),
+                Some(&(crc32, size)) if (crc32, size) != (*expected_crc32, *expected_size) => {
+                    mismatches.push(Mismatch::Differs {
+                        name: name.clone(),
+                        expected_crc32: *expected_crc32,
+                        crc32,
+                        expected_size: *expected_size,
+                        size,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        let mut extra = BTreeSet::new();
+        for name in self.names() {
+            if !seen.contains(name.as_str()) && extra.insert(name.clone()) {
+                mismatches.push(Mismatch::Extra { name });
+            }
+        }
+        mismatches
+    }
+}
+
+/// Parse a manifest for [`Zip::matches_manifest`] into `(name, crc32, uncompressed_size)` tuples
+///
+/// The format is the output of [`Zip::manifest`] (`zp --format manifest`): a header line of
+/// tab-separated column names, then one tab-separated line per item.
+/// Only the `name`, `crc32` (decimal), and `uncompressed_size` columns are required; other
+/// columns are ignored, in any order.
+/// Empty lines are skipped.
+pub fn parse_manifest(s: &str) -> Result<Vec<(String, u32, u64)>, String> {
+    let mut lines = s.lines().enumerate().filter(|(_, line)| !line.is_empty());
+    let header = lines.next().map(|(_, line)| line).unwrap_or("");
+    let column = |name: &str| header.split('\t').position(|x| x == name);
+    let missing = || {
+        String::from(
+            "Manifest header must have the `name`, `crc32`, and `uncompressed_size` columns",
+        )
+    };
+    let name = column("name").ok_or_else(missing)?;
+    let crc32 = column("crc32").ok_or_else(missing)?;
+    let size = column("uncompressed_size").ok_or_else(missing)?;
+    lines
+        .map(|(n, line)| {
+            let fields = line.split('\t').collect::<Vec<_>>();
+            let field = |i: usize| fields.get(i).copied();
+            match (
+                field(name),
+                field(crc32).and_then(|x| x.parse().ok()),
+                field(size).and_then(|x| x.parse().ok()),
+            ) {
+                (Some(name), Some(crc32), Some(size)) => Ok((name.to_string(), crc32, size)),
+                _ => Err(format!("Invalid manifest line {}: `{line}`", n + 1)),
+            }
+        })
+        .collect()
+}
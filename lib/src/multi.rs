@@ -0,0 +1,85 @@
+use crate::*;
+
+impl Zip {
+    /// Process a file of concatenated zip files at path (e.g. `cat a.zip b.zip > ab.zip`); see
+    /// [`ZipReader::open_multi`]
+    pub fn from_multi<P>(path: P) -> Result<Vec<Self>, String>
+    where
+        P: Into<PathBuf>,
+    {
+        Zip::reader().open_multi(path)
+    }
+
+    /// Process the raw bytes of one or more concatenated zip files with the default options; see
+    /// [`ZipReader::process_multi`]
+    pub fn process_multi<R>(r: &mut BufReader<R>) -> Result<Vec<Self>, String>
+    where
+        R: Read + Seek,
+    {
+        Zip::reader().process_multi(r)
+    }
+}
+
+impl ZipReader {
+    /// Process a file of concatenated zip files at path with the options; see
+    /// [`ZipReader::process_multi`]
+    pub fn open_multi<P>(&self, path: P) -> Result<Vec<Zip>, String>
+    where
+        P: Into<PathBuf>,
+    {
+        let path: PathBuf = path.into();
+        let zips = self.process_multi(&mut open_file(&path)?)?;
+        Ok(zips
+            .into_iter()
+            .map(|zip| zip.with_path(path.clone()))
+            .collect())
+    }
+
+    /// Process the raw bytes of one or more concatenated zip files with the options
+    ///
+    /// Parses a zip file like [`ZipReader::process`]; then, as long as its trailing data starts
+    /// with a local file header or end of central directory record signature, parses another
+    /// zip file there.
+    /// Each zip file's [base offset](Zip::base_offset) is where it starts, and its
+    /// [trailing bytes](Zip::trailing_bytes) exclude the zip files after it.
+    /// Trailing data that is not another zip file ends the list, as does a zip file that
+    /// consumed no bytes or a [truncated](Zip::truncated) record; an error in any zip file fails
+    /// the whole list.
+    /// The encoding, strict mode, and allocation limit apply to each zip file; hashing does not
+    /// apply, since the zip files share the bytes of one file.
+    pub fn process_multi<R>(&self, r: &mut BufReader<R>) -> Result<Vec<Zip>, String>
+    where
+        R: Read + Seek,
+    {
+        let io = |e: std::io::Error| e.to_string();
+        if let Some(offset) = self.offset {
+            r.seek(SeekFrom::Start(offset)).map_err(io)?;
+        }
+        let mut zips = vec![self.parse(r)?];
+        let end = r.seek(SeekFrom::End(0)).map_err(io)?;
+        while let Some(zip) = zips.last_mut() {
+            let next = end - zip.trailing;
+            if zip.trailing == 0 || zip.truncated().is_some() || next <= zip.base {
+                break;
+            }
+            r.seek(SeekFrom::Start(next)).map_err(io)?;
+            let mut signature = vec![];
+            r.by_ref().take(4).read_to_end(&mut signature).map_err(io)?;
+            if signature != b"PK\x03\x04" && signature != b"PK\x05\x06" {
+                break;
+            }
+            zip.trailing = 0;
+            r.seek(SeekFrom::Start(next)).map_err(io)?;
+            zips.push(self.parse(r)?);
+        }
+        for zip in &zips {
+            if self.strict {
+                zip.strict()?;
+            }
+        }
+        Ok(zips
+            .into_iter()
+            .map(|zip| zip.with_encoding(self.encoding))
+            .collect())
+    }
+}
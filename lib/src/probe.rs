@@ -0,0 +1,156 @@
+use crate::*;
+
+/// What [`Zip::probe`] found at the start (or end) of a file
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProbeResult {
+    /// Whether the file starts with a local file header or ends with an end of central directory
+    /// record
+    pub zip: bool,
+
+    /// Whether the first local file header or the end of central directory record uses Zip64
+    /// fields
+    pub zip64: bool,
+
+    /// File name of the first local file, decoded with [`Encoding::Auto`]
+    pub name: Option<String>,
+
+    /// Compression method of the first local file
+    pub compression: Option<u16>,
+}
+
+impl Zip {
+    /// Identify a zip file by reading only its first local file header, or, if it does not
+    /// start with one (an empty zip file or one with a prefix), its end of central directory
+    /// record
+    ///
+    /// Reads at most the first local file header with its file name and extra field, or the last
+    /// 64 KiB, regardless of the size of the zip file, and never reads file data.
+    /// The reader is returned to its starting position.
+    pub fn probe<R>(r: &mut R) -> Result<ProbeResult, String>
+    where
+        R: Read + Seek,
+    {
+        let io = |e: std::io::Error| e.to_string();
+        let start = r.stream_position().map_err(io)?;
+        let result = probe_local_file(r)?;
+        let result = match result {
+            Some(result) => result,
+            None => probe_end_of_central_directory(r, start)?,
+        };
+        r.seek(SeekFrom::Start(start)).map_err(io)?;
+        Ok(result)
+    }
+}
+
+/// Read up to `n` bytes
+fn read_up_to<R: Read>(r: &mut R, n: u64) -> Result<Vec<u8>, String> {
+    let mut b = vec![];
+    r.take(n).read_to_end(&mut b).map_err(|e| e.to_string())?;
+    Ok(b)
+}
+
+/// Probe a local file header at the current position
+fn probe_local_file<R: Read>(r: &mut R) -> Result<Option<ProbeResult>, String> {
+    let header = read_up_to(r, 30)?;
+    let (name_length, extra_length) = match local_header_lengths(&header) {
+        Some(lengths) if header.starts_with(b"PK\x03\x04") => lengths,
+        _ => return Ok(None),
+    };
+    let u16_at = |n: usize| u16::from_le_bytes([header[n], header[n + 1]]);
+    let u32_at =
+        |n: usize| u32::from_le_bytes([header[n], header[n + 1], header[n + 2], header[n + 3]]);
+    let name = read_up_to(r, name_length as u64)?;
+    let extra = read_up_to(r, extra_length as u64)?;
+    let zip64 = u32_at(18) == u32::MAX
+        || u32_at(22) == u32::MAX
+        || extra_fields(&extra).map_or(false, |fields| fields.iter().any(|i| i.tag == 0x0001));
+    Ok(Some(ProbeResult {
+        zip: true,
+        zip64,
+        name: Some(Encoding::Auto.decode(&name, u16_at(6)).into_owned()),
+        compression: Some(u16_at(8)),
+    }))
+}
+
+/// Read the last 64 KiB (the largest comment plus the end of central directory record) of the
+/// file, or from `start` if it is shorter, and return the offset where they begin with the bytes
+pub(crate) fn read_tail<R>(r: &mut R, start: u64) -> Result<(u64, Vec<u8>), String>
+where
+    R: Read + Seek,
+{
+    let io = |e: std::io::Error| e.to_string();
+    let end = r.seek(SeekFrom::End(0)).map_err(io)?;
+    let tail_start = end.saturating_sub(22 + u16::MAX as u64).max(start);
+    r.seek(SeekFrom::Start(tail_start)).map_err(io)?;
+    Ok((tail_start, read_up_to(r, end - tail_start)?))
+}
+
+/// Index of the last complete end of central directory record in `tail`; see [`read_tail`]
+pub(crate) fn find_end_of_central_directory(tail: &[u8]) -> Option<usize> {
+    (0..tail.len().saturating_sub(21))
+        .rev()
+        .find(|&n| tail[n..].starts_with(b"PK\x05\x06"))
+}
+
+/// Length of the bytes before the first local file header of a zip file at `start` whose first
+/// record can't be read, such as a self-extracting stub, worked out from the end
+///
+/// The central directory ends at the end of central directory record, so it starts `cd_size`
+/// bytes before it and the zip file `cd_offset` bytes before that; the first local file header is
+/// the first central directory file header's `lfh_offset` after the start of the zip file.
+/// This finds the prefix whether or not the offsets include it.
+/// Returns `None` if any of these records is missing or the first local file header is at
+/// `start`.
+pub(crate) fn prefix_length<R>(r: &mut R, start: u64) -> Result<Option<u64>, String>
+where
+    R: Read + Seek,
+{
+    let io = |e: std::io::Error| e.to_string();
+    let (tail_start, tail) = read_tail(r, start)?;
+    let n = match find_end_of_central_directory(&tail) {
+        Some(n) => n,
+        None => return Ok(None),
+    };
+    let u32_at = |b: &[u8], i: usize| u32::from_le_bytes([b[i], b[i + 1], b[i + 2], b[i + 3]]);
+    let cd_start = (tail_start + n as u64).checked_sub(u32_at(&tail, n + 12) as u64);
+    let zip_start = cd_start.and_then(|x| x.checked_sub(u32_at(&tail, n + 16) as u64));
+    let (cd_start, zip_start) = match (cd_start, zip_start) {
+        (Some(cd_start), Some(zip_start)) if zip_start >= start => (cd_start, zip_start),
+        _ => return Ok(None),
+    };
+    r.seek(SeekFrom::Start(cd_start)).map_err(io)?;
+    let header = read_up_to(r, 46)?;
+    if header.len() < 46 || !header.starts_with(b"PK\x01\x02") {
+        return Ok(None);
+    }
+    let lfh = zip_start + u32_at(&header, 42) as u64;
+    r.seek(SeekFrom::Start(lfh)).map_err(io)?;
+    if lfh == start || read_up_to(r, 4)? != b"PK\x03\x04" {
+        return Ok(None);
+    }
+    Ok(Some(lfh - start))
+}
+
+/// Probe the last end of central directory record within the last 64 KiB (the largest comment)
+fn probe_end_of_central_directory<R>(r: &mut R, start: u64) -> Result<ProbeResult, String>
+where
+    R: Read + Seek,
+{
+    let (_, tail) = read_tail(r, start)?;
+    Ok(match find_end_of_central_directory(&tail) {
+        Some(n) => {
+            let eocd = &tail[n..n + 22];
+            let saturated = eocd[8..10] == [0xff; 2]
+                || eocd[10..12] == [0xff; 2]
+                || eocd[12..16] == [0xff; 4]
+                || eocd[16..20] == [0xff; 4];
+            let locator = n >= 20 && tail[n - 20..].starts_with(b"PK\x06\x07");
+            ProbeResult {
+                zip: true,
+                zip64: saturated || locator,
+                ..Default::default()
+            }
+        }
+        None => ProbeResult::default(),
+    })
+}
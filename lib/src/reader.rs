@@ -0,0 +1,129 @@
+use crate::*;
+
+/// Builder that combines the options for reading a zip file; see [`Zip::reader`]
+///
+/// ```
+/// use zp_lib::{Encoding, Zip};
+///
+/// let zip = Zip::reader()
+///     .encoding(Encoding::Utf8)
+///     .max_alloc(1 << 20)
+///     .strict(true)
+///     .open("../exercise.zip")
+///     .unwrap();
+/// assert_eq!(zip.names().len(), 13);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ZipReader {
+    pub(crate) encoding: Encoding,
+    pub(crate) strict: bool,
+    pub(crate) lenient: bool,
+    pub(crate) offset: Option<u64>,
+    pub(crate) max_alloc: Option<u64>,
+
+    #[cfg(feature = "hash")]
+    pub(crate) hash: bool,
+}
+
+impl ZipReader {
+    /// Create a reader with the default options (same as [`Zip::from`] and [`Zip::process`])
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decode file names and comments with `encoding`; see [`Zip::with_encoding`]
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Reject any nonconformance; see [`Zip::strict`]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Keep the records parsed before any damage instead of failing; see
+    /// [`Zip::process_lenient`]
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Start parsing at byte `offset` (e.g. after a self-extracting stub); see
+    /// [`Zip::from_offset`]
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Refuse any record with a declared length (file name, extra field, comment, or compressed
+    /// size) larger than `bytes`, to bound the memory a single record can take when reading
+    /// untrusted zip files
+    ///
+    /// Declared lengths are always limited to the remaining bytes of the file; this limit is
+    /// tighter.
+    /// A record over the limit is an error, or with [`ZipReader::lenient`] stops parsing like
+    /// any other damage.
+    /// The file data of a streamed local file does not declare its size, so the scan for its end
+    /// fails once it passes the limit instead.
+    pub fn max_alloc(mut self, bytes: u64) -> Self {
+        self.max_alloc = Some(bytes);
+        self
+    }
+
+    /// Process a zip file at path with the options
+    pub fn open<P>(&self, path: P) -> Result<Zip, String>
+    where
+        P: Into<PathBuf>,
+    {
+        let path: PathBuf = path.into();
+        let zip = self.process(&mut open_file(&path)?)?;
+        Ok(zip.with_path(path))
+    }
+
+    /// Process the raw bytes of a zip file with the options
+    ///
+    /// Parsing begins at the configured offset, or at the reader's current position.
+    pub fn process<R>(&self, r: &mut BufReader<R>) -> Result<Zip, String>
+    where
+        R: Read + Seek,
+    {
+        if let Some(offset) = self.offset {
+            r.seek(SeekFrom::Start(offset)).map_err(|e| e.to_string())?;
+        }
+        #[cfg(feature = "hash")]
+        let zip = if self.hash {
+            self.parse_hashed(r)?
+        } else {
+            self.parse(r)?
+        };
+        #[cfg(not(feature = "hash"))]
+        let zip = self.parse(r)?;
+        // Strict mode checks the names with the chosen encoding
+        let zip = zip.with_encoding(self.encoding);
+        if self.strict {
+            zip.strict()?;
+        }
+        Ok(zip)
+    }
+
+    /// Parse normally or leniently, within the allocation limit
+    pub(crate) fn parse<R>(&self, r: &mut BufReader<R>) -> Result<Zip, String>
+    where
+        R: Read + Seek,
+    {
+        let max_alloc = self.max_alloc.unwrap_or(u64::MAX);
+        Zip::parse(r, 0, self.lenient, max_alloc)
+    }
+}
+
+impl Zip {
+    /// Start configuring how to read a zip file; see [`ZipReader`]
+    ///
+    /// [`Zip::from`] and [`Zip::process`] remain the convenient way to read a zip file with the
+    /// default options.
+    pub fn reader() -> ZipReader {
+        ZipReader::new()
+    }
+}
@@ -0,0 +1,53 @@
+use crate::*;
+
+/// Version of the [`Zip::sbom`] JSON shape; incremented whenever a field is renamed or removed
+pub const SBOM_VERSION: u32 = 1;
+
+impl Zip {
+    /// Generate a minimal software bill of materials: a JSON inventory of the files (folders are
+    /// omitted) for ingestion by SBOM tooling
+    ///
+    /// The object has `format` (`"zp-sbom"`), `version` ([`SBOM_VERSION`]), and `files`, an array
+    /// with an object per file with the `name`, `size` (uncompressed), `crc32` (decimal, as in
+    /// [`Zip::json`]), and `sha256` of the decompressed data (`null` if the item is encrypted,
+    /// has no local file, uses an unsupported compression method, or fails the CRC-32 check (see
+    /// [`Zip::extract`]), or if the `hash` feature (enabled by default) is disabled).
+    /// See [`Json::to_json`] for `pretty`.
+    pub fn sbom(&self, pretty: bool) -> String {
+        let files = self
+            .central_directory()
+            .filter(|i| !i.is_dir())
+            .map(|i| {
+                Json::object([
+                    ("name", Json::String(i.name(self.encoding).into_owned())),
+                    ("size", Json::Number(i.uncompressed_size() as u64)),
+                    ("crc32", Json::Number(i.crc32() as u64)),
+                    (
+                        "sha256",
+                        self.file_sha256(i).map_or(Json::Null, Json::String),
+                    ),
+                ])
+            })
+            .collect();
+        Json::object([
+            ("format", Json::String(String::from("zp-sbom"))),
+            ("version", Json::Number(SBOM_VERSION as u64)),
+            ("files", Json::Array(files)),
+        ])
+        .to_json(pretty)
+    }
+
+    /// SHA-256 of the decompressed data of an item for [`Zip::sbom`]
+    #[cfg(feature = "hash")]
+    fn file_sha256(&self, header: &CentralDirectoryFileHeader) -> Option<String> {
+        let data = self.extract_file(header).ok()?;
+        Some(sha256(&data))
+    }
+
+    /// SHA-256 of the decompressed data of an item for [`Zip::sbom`] (always `None` without the
+    /// `hash` feature)
+    #[cfg(not(feature = "hash"))]
+    fn file_sha256(&self, _header: &CentralDirectoryFileHeader) -> Option<String> {
+        None
+    }
+}
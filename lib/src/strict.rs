@@ -0,0 +1,145 @@
+use crate::*;
+
+impl Zip {
+    /// Process a zip file at path and reject any nonconformance; see [`Zip::nonconformances`]
+    pub fn from_strict<P>(path: P) -> Result<Self, String>
+    where
+        P: Into<PathBuf>,
+    {
+        let zip = Zip::from(path)?;
+        zip.strict()?;
+        Ok(zip)
+    }
+
+    /// Process the raw bytes of a zip file and reject any nonconformance; see
+    /// [`Zip::nonconformances`]
+    pub fn process_strict<R>(r: &mut BufReader<R>) -> Result<Self, String>
+    where
+        R: Read + Seek,
+    {
+        let zip = Zip::process(r)?;
+        zip.strict()?;
+        Ok(zip)
+    }
+
+    /// Return the first nonconformance as an error
+    pub fn strict(&self) -> Result<(), String> {
+        match self.nonconformances().into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// List the ways the zip file deviates from the specification that are otherwise tolerated
+    ///
+    /// Strict mode ([`Zip::from_strict`], [`Zip::process_strict`], `zp --strict`) rejects:
+    ///
+    /// * A missing end of central directory record
+    /// * Trailing data after the end of central directory record or a truncated final record
+    /// * File names, file comments, or the zip file comment that are not valid UTF-8 and
+    ///   would be guessed as CP437 by [`Encoding::Auto`], or that decode lossily with another
+    ///   [`Encoding`] (e.g. invalid UTF-8 with [`Encoding::Utf8`] or the UTF-8 flag); choosing
+    ///   [`Encoding::Cp437`] accepts any bytes
+    /// * End of central directory record entry counts that differ from the number of central
+    ///   directory file headers
+    /// * End of central directory record central directory size or offset that differ from the
+    ///   actual size or offset of the central directory
+    pub fn nonconformances(&self) -> Vec<String> {
+        let mut r = vec![];
+
+        // Lossy decoding
+        let encoding = self.encoding();
+        for (offset, entry) in self.entries_with_offsets() {
+            let fields: Vec<(&str, &[u8], Cow<str>)> = match entry {
+                Entry::LocalFile(i) => vec![("File name", i.file_name(), i.name(encoding))],
+                Entry::ArchiveExtraDataRecord(_) => vec![],
+                Entry::CentralDirectoryFileHeader(i) => vec![
+                    ("File name", i.file_name(), i.name(encoding)),
+                    ("File comment", i.file_comment(), i.comment(encoding)),
+                ],
+                Entry::EndOfCentralDirectoryRecord(i) => {
+                    vec![(
+                        "Zip file comment",
+                        i.zip_file_comment(),
+                        i.comment(encoding),
+                    )]
+                }
+            };
+            for (field, bytes, decoded) in fields {
+                if std::str::from_utf8(bytes).is_ok() {
+                    continue;
+                }
+                if encoding == Encoding::Auto {
+                    r.push(format!(
+                        "{field} at offset {offset} is not valid UTF-8: `{}`",
+                        hex::encode(bytes),
+                    ));
+                } else if decoded.contains('\u{fffd}') {
+                    r.push(format!(
+                        "{field} at offset {offset} cannot be decoded without loss: `{}`",
+                        hex::encode(bytes),
+                    ));
+                }
+            }
+        }
+
+        // End of central directory record
+        let eocd = self
+            .entries_with_offsets()
+            .find_map(|(offset, entry)| match entry {
+                Entry::EndOfCentralDirectoryRecord(i) => Some((i, offset)),
+                _ => None,
+            });
+        match eocd {
+            Some((eocd, eocd_offset)) => {
+                let headers = self
+                    .entries_with_offsets()
+                    .filter_map(|(offset, entry)| match entry {
+                        Entry::CentralDirectoryFileHeader(i) => Some((i, offset)),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                let count = headers.len();
+                let size = headers.iter().map(|(i, _)| i.record_size()).sum::<u64>();
+                let offset = headers.first().map(|(_, o)| *o).unwrap_or(eocd_offset);
+                if eocd.disk_entries() as usize != count {
+                    r.push(format!(
+                        "End of central directory record disk entries ({}) does not match the \
+                        number of central directory file headers ({count})",
+                        eocd.disk_entries(),
+                    ));
+                }
+                if eocd.total_entries() as usize != count {
+                    r.push(format!(
+                        "End of central directory record total entries ({}) does not match the \
+                        number of central directory file headers ({count})",
+                        eocd.total_entries(),
+                    ));
+                }
+                if eocd.cd_size() as u64 != size {
+                    r.push(format!(
+                        "End of central directory record central directory size ({}) does not \
+                        match the actual size ({size})",
+                        eocd.cd_size(),
+                    ));
+                }
+                if self.base + eocd.cd_offset() as u64 != offset {
+                    r.push(format!(
+                        "End of central directory record central directory offset ({}) does \
+                        not match the actual offset ({})",
+                        eocd.cd_offset(),
+                        offset - self.base,
+                    ));
+                }
+            }
+            None => r.push(String::from("Missing end of central directory record")),
+        }
+
+        // Trailing data
+        if self.trailing > 0 {
+            r.push(format!("{} bytes of trailing data", self.trailing));
+        }
+
+        r
+    }
+}
@@ -0,0 +1,195 @@
+use crate::*;
+use std::fmt::Write;
+
+/// Default maximum folder depth of a tree; see [`TreeOptions::max_depth`]
+pub const DEFAULT_MAX_DEPTH: usize = 16;
+
+/// Characters used to draw the branches of a tree; see [`TreeOptions`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TreeStyle {
+    /// Box-drawing characters (`├── `, `└── `, `│   `)
+    Unicode,
+
+    /// ASCII characters (`|-- `, `` `-- ``, `|   `) for terminals that render box-drawing
+    /// characters poorly
+    Ascii,
+}
+
+impl Default for TreeStyle {
+    fn default() -> Self {
+        TreeStyle::Unicode
+    }
+}
+
+impl TreeStyle {
+    /// Branch prefixes: (item, last item, continuation, blank)
+    fn branches(&self) -> [&'static str; 4] {
+        match self {
+            TreeStyle::Unicode => ["├── ", "└── ", "│   ", "    "],
+            TreeStyle::Ascii => ["|-- ", "`-- ", "|   ", "    "],
+        }
+    }
+}
+
+impl std::str::FromStr for TreeStyle {
+    type Err = String;
+
+    /// Parse a tree style name: `unicode` or `ascii`
+    fn from_str(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "unicode" => Ok(TreeStyle::Unicode),
+            "ascii" => Ok(TreeStyle::Ascii),
+            _ => Err(format!(
+                "Unknown tree style: `{name}` (expected `unicode` or `ascii`)"
+            )),
+        }
+    }
+}
+
+/// Tree output options; see [`Zip::tree_with`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TreeOptions {
+    /// Characters used to draw the branches
+    pub style: TreeStyle,
+
+    /// Append the uncompressed size of each file
+    pub sizes: bool,
+
+    /// Append the total uncompressed size of the files under each folder, recursively (see
+    /// [`Zip::directory_sizes`])
+    pub folder_sizes: bool,
+
+    /// Append the modified date/time of each item
+    pub dates: bool,
+
+    /// Append `exec` to executable files (see [`CentralDirectoryFileHeader::is_executable`])
+    pub executable: bool,
+
+    /// Maximum folder depth (default: [`DEFAULT_MAX_DEPTH`]); the items below it are counted
+    /// instead of listed, which bounds the work for pathologically deep names
+    pub max_depth: Option<usize>,
+}
+
+impl TreeOptions {
+    /// Maximum folder depth
+    pub fn max_depth(&self) -> usize {
+        self.max_depth.unwrap_or(DEFAULT_MAX_DEPTH)
+    }
+}
+
+/// Item or folder in a tree of item names
+#[derive(Default)]
+struct Node<'a> {
+    header: Option<&'a CentralDirectoryFileHeader>,
+    children: BTreeMap<String, Node<'a>>,
+
+    /// Total uncompressed size of the files under a folder, with [`TreeOptions::folder_sizes`]
+    folder_size: Option<u64>,
+
+    /// Number of items below the maximum depth
+    omitted: usize,
+}
+
+impl Zip {
+    /// Generate a folder tree of the item names
+    pub fn tree(&self) -> String {
+        self.tree_with(&TreeOptions::default())
+    }
+
+    /// Generate a folder tree of the item names with options
+    ///
+    /// Folders are listed with a trailing `/`, including implicit folders that only appear in the
+    /// names of the items they contain.
+    /// With [`TreeOptions::folder_sizes`], a folder's size comes first, before its date/time.
+    /// Children are sorted by name.
+    /// Items nested deeper than the [maximum depth](TreeOptions::max_depth) are replaced by a
+    /// line with their count under the deepest folder shown.
+    pub fn tree_with(&self, options: &TreeOptions) -> String {
+        let sizes = if options.folder_sizes {
+            self.directory_sizes()
+        } else {
+            BTreeMap::new()
+        };
+        let mut root = Node::default();
+        for i in self.central_directory() {
+            let mut node = &mut root;
+            let name = i.name(self.encoding());
+            let mut parts = name.split_inclusive('/');
+            let mut end = 0;
+            for part in parts.by_ref().take(options.max_depth()) {
+                end += part.len();
+                node = node.children.entry(part.to_string()).or_default();
+                node.folder_size = sizes.get(&name[..end]).copied();
+            }
+            if parts.next().is_some() {
+                node.omitted += 1;
+            } else {
+                node.header = Some(i);
+            }
+        }
+        let mut s = String::new();
+        for (name, node) in &root.children {
+            write_node(&mut s, name, node, options);
+            write_children(&mut s, node, "", options);
+        }
+        if root.omitted > 0 {
+            write_omitted(&mut s, &root, options);
+        }
+        s
+    }
+}
+
+/// Write the children of `node`, each line starting with `prefix` and its branch
+fn write_children(s: &mut String, node: &Node, prefix: &str, options: &TreeOptions) {
+    let [item, last_item, continuation, blank] = options.style.branches();
+    let count = node.children.len();
+    for (n, (name, child)) in node.children.iter().enumerate() {
+        let last = n + 1 == count && node.omitted == 0;
+        s.push_str(prefix);
+        s.push_str(if last { last_item } else { item });
+        write_node(s, name, child, options);
+        let prefix = format!("{prefix}{}", if last { blank } else { continuation });
+        write_children(s, child, &prefix, options);
+    }
+    if node.omitted > 0 {
+        s.push_str(prefix);
+        s.push_str(last_item);
+        write_omitted(s, node, options);
+    }
+}
+
+/// Write the number of items below the maximum depth under `node`
+fn write_omitted(s: &mut String, node: &Node, options: &TreeOptions) {
+    let items = if node.omitted == 1 { "item" } else { "items" };
+    writeln!(
+        s,
+        "... ({} {items} below the maximum depth of {})",
+        node.omitted,
+        options.max_depth(),
+    )
+    .unwrap();
+}
+
+/// Write the name of a node and the optional size, date/time, and executable marker
+fn write_node(s: &mut String, name: &str, node: &Node, options: &TreeOptions) {
+    s.push_str(name);
+    let mut details = vec![];
+    if let Some(size) = node.folder_size {
+        details.push(size.to_string());
+    }
+    if let Some(header) = node.header {
+        if options.sizes && !header.is_dir() {
+            details.push(header.uncompressed_size().to_string());
+        }
+        if options.dates {
+            details.push(header.modified());
+        }
+        if options.executable && header.is_executable() == Some(true) {
+            details.push(String::from("exec"));
+        }
+    }
+    if !details.is_empty() {
+        write!(s, " ({})", details.join(", ")).unwrap();
+    }
+    s.push('\n');
+}
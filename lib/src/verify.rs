@@ -0,0 +1,93 @@
+use crate::*;
+
+/// CRC-32 check of an item; see [`Zip::verify`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Verification {
+    /// Item name
+    pub name: String,
+
+    /// CRC-32 stored in the central directory file header
+    pub expected: u32,
+
+    /// CRC-32 of the item's (decompressed) file data, or why it was not computed
+    pub computed: Result<u32, String>,
+}
+
+impl Verification {
+    /// Whether the computed CRC-32 matches the stored CRC-32
+    pub fn is_ok(&self) -> bool {
+        self.computed == Ok(self.expected)
+    }
+
+    /// Whether the computed CRC-32 differs from the stored CRC-32
+    pub fn is_bad(&self) -> bool {
+        matches!(self.computed, Ok(crc) if crc != self.expected)
+    }
+
+    /// Short status: `OK`, `BAD`, or `-` if the CRC-32 was not computed
+    pub fn status(&self) -> &'static str {
+        match self.computed {
+            Ok(crc) if crc == self.expected => "OK",
+            Ok(_) => "BAD",
+            Err(_) => "-",
+        }
+    }
+}
+
+impl std::fmt::Display for Verification {
+    /// One-line format: `<name>: OK`, `<name>: BAD (expected <crc>, computed <crc>)`, or
+    /// `<name>: - (<reason>)`
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.computed {
+            Ok(crc) if *crc == self.expected => write!(f, "{}: OK", self.name),
+            Ok(crc) => write!(
+                f,
+                "{}: BAD (expected 0x{:08x}, computed 0x{crc:08x})",
+                self.name, self.expected,
+            ),
+            Err(e) => write!(f, "{}: - ({e})", self.name),
+        }
+    }
+}
+
+impl Zip {
+    /// Check the CRC-32 of each item against the CRC-32 stored in its central directory file
+    /// header
+    ///
+    /// By default, only stored (uncompressed) items are checked, since their file data is the
+    /// uncompressed data.
+    /// If `deep` is true, compressed items are decompressed (see [`decompress`]) and the CRC-32 of
+    /// the decompressed data is checked.
+    /// Encrypted items and items without a local file are not checked.
+    pub fn verify(&self, deep: bool) -> Vec<Verification> {
+        self.central_directory()
+            .map(|i| Verification {
+                name: i.name(self.encoding()).into_owned(),
+                expected: i.crc32(),
+                computed: self.computed_crc32(i, deep),
+            })
+            .collect()
+    }
+
+    /// Compute the CRC-32 of the file data of an item for [`Zip::verify`]
+    fn computed_crc32(
+        &self,
+        header: &CentralDirectoryFileHeader,
+        deep: bool,
+    ) -> Result<u32, String> {
+        let local = self
+            .local_file_for(header)
+            .ok_or_else(|| String::from("missing local file"))?;
+        if header.is_encrypted() {
+            return Err(String::from("encrypted"));
+        }
+        match local.compression() {
+            0 => Ok(crc32(local.file_data())),
+            _ if !deep => Err(String::from("compressed; use deep verification")),
+            compression => {
+                let size = u64::from(header.uncompressed_size());
+                Ok(crc32(&decompress(compression, local.file_data(), size)?))
+            }
+        }
+    }
+}
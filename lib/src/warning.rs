@@ -0,0 +1,121 @@
+use crate::*;
+
+/// Uncompressed to compressed size ratio above which an item is reported; see
+/// [`Warning::SuspiciousRatio`]
+pub const SUSPICIOUS_RATIO: u64 = 100;
+
+/// Non-fatal finding in a zip file that parsed successfully; see [`Zip::warnings`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Warning {
+    /// A central directory file name is not valid in the selected [`Encoding`], so its decoded
+    /// name has `U+FFFD` replacement characters; `name` is the raw file name in hex
+    LossyName { offset: u64, name: String },
+
+    /// Several central directory file headers have the same name, so extracting them would keep
+    /// only one of the items
+    DuplicateName { name: String, count: usize },
+
+    /// The end of central directory record's total entries differs from the number of central
+    /// directory file headers
+    EntryCountMismatch { declared: u16, actual: usize },
+
+    /// An item's uncompressed size is more than [`SUSPICIOUS_RATIO`] times its compressed size,
+    /// as in a zip bomb
+    SuspiciousRatio {
+        name: String,
+        compressed_size: u32,
+        uncompressed_size: u32,
+    },
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Warning::LossyName { offset, name } => write!(
+                f,
+                "File name `{name}` (hex) at offset {offset} cannot be decoded without loss",
+            ),
+            Warning::DuplicateName { name, count } => {
+                write!(f, "File name `{name}` appears {count} times")
+            }
+            Warning::EntryCountMismatch { declared, actual } => write!(
+                f,
+                "End of central directory record declares {declared} entries but there are \
+                {actual} central directory file headers",
+            ),
+            Warning::SuspiciousRatio {
+                name,
+                compressed_size,
+                uncompressed_size,
+            } => write!(
+                f,
+                "Item `{name}` expands from {compressed_size} to {uncompressed_size} bytes, more \
+                than {SUSPICIOUS_RATIO} times",
+            ),
+        }
+    }
+}
+
+impl Zip {
+    /// List the non-fatal findings that do not prevent reading the zip file but may indicate a
+    /// careless or malicious writer, e.g. for `zp --strict-warnings` to fail CI on them
+    ///
+    /// Exactly these are warnings, in this order:
+    ///
+    /// * Central directory file names that decode lossily with the [`Encoding`] (e.g. invalid
+    ///   UTF-8 with [`Encoding::Utf8`] or the UTF-8 flag; [`Encoding::Auto`] falls back to CP437,
+    ///   which is lossless)
+    /// * Duplicate central directory file names, once per name in order of first occurrence
+    /// * An end of central directory record whose total entries differ from the number of
+    ///   central directory file headers (not checked for Zip64 zip files; see [`Zip::is_zip64`])
+    /// * Files whose uncompressed size is more than [`SUSPICIOUS_RATIO`] times their compressed
+    ///   size
+    ///
+    /// The interoperability problems of [`Zip::check`], the mismatches of [`Zip::audit`], and the
+    /// nonconformances of [`Zip::nonconformances`] are separate.
+    pub fn warnings(&self) -> Vec<Warning> {
+        let mut warnings = vec![];
+        for (offset, entry) in self.entries_with_offsets() {
+            if let Entry::CentralDirectoryFileHeader(i) = entry {
+                let bytes = i.file_name();
+                if std::str::from_utf8(bytes).is_err()
+                    && i.name(self.encoding()).contains('\u{fffd}')
+                {
+                    let name = hex::encode(bytes);
+                    warnings.push(Warning::LossyName { offset, name });
+                }
+            }
+        }
+        let names = self.names();
+        let mut counts = BTreeMap::new();
+        for name in &names {
+            *counts.entry(name.as_str()).or_insert(0) += 1;
+        }
+        for name in &names {
+            if let Some(count) = counts.remove(name.as_str()).filter(|&count| count > 1) {
+                let name = name.clone();
+                warnings.push(Warning::DuplicateName { name, count });
+            }
+        }
+        if let Some(eocd) = self.end_of_central_directory() {
+            let actual = self.central_directory().count();
+            if !self.is_zip64() && eocd.total_entries() as usize != actual {
+                warnings.push(Warning::EntryCountMismatch {
+                    declared: eocd.total_entries(),
+                    actual,
+                });
+            }
+        }
+        for i in self.central_directory() {
+            let limit = SUSPICIOUS_RATIO * (i.compressed_size() as u64).max(1);
+            if !i.is_dir() && i.uncompressed_size() as u64 > limit {
+                warnings.push(Warning::SuspiciousRatio {
+                    name: i.name(self.encoding()).into_owned(),
+                    compressed_size: i.compressed_size(),
+                    uncompressed_size: i.uncompressed_size(),
+                });
+            }
+        }
+        warnings
+    }
+}
@@ -1,82 +1,446 @@
 use crate::*;
+use std::fmt::Write;
+use std::io::BufRead;
 
 /// Struct API
 #[derive(Debug)]
 pub struct Zip {
     path: Option<PathBuf>,
-    entries: Entries,
+    pub(crate) entries: Entries,
+    pub(crate) offsets: Vec<u64>,
+    pub(crate) base: u64,
+    pub(crate) trailing: u64,
+    truncated: Option<String>,
+    encoding: Encoding,
+    pub(crate) sha256: Option<String>,
+}
+
+/// Summary statistics of a zip file's central directory; see [`Zip::stats`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ZipStats {
+    /// Number of files
+    pub files: usize,
+
+    /// Number of folders
+    pub dirs: usize,
+
+    /// Total compressed size of all items
+    pub compressed_size: u64,
+
+    /// Total uncompressed size of all items
+    pub uncompressed_size: u64,
+
+    /// Number of encrypted items
+    pub encrypted: usize,
+}
+
+impl std::fmt::Display for ZipStats {
+    /// One-line format: `N files, M dirs, total <size>, <encrypted?>`
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} files, {} dirs, total {}, {}",
+            self.files,
+            self.dirs,
+            self.uncompressed_size,
+            if self.encrypted > 0 {
+                "encrypted"
+            } else {
+                "not encrypted"
+            },
+        )
+    }
+}
+
+/// Number of records of each type; see [`Zip::counts`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RecordCounts {
+    /// Number of local files
+    pub local_files: usize,
+
+    /// Number of archive extra data records
+    pub archive_extra_data_records: usize,
+
+    /// Number of central directory file headers
+    pub central_directory_file_headers: usize,
+
+    /// Number of end of central directory records
+    pub end_of_central_directory_records: usize,
+}
+
+impl RecordCounts {
+    /// Whether there is one central directory file header per local file and exactly one end of
+    /// central directory record
+    pub fn is_consistent(&self) -> bool {
+        self.local_files == self.central_directory_file_headers
+            && self.end_of_central_directory_records == 1
+    }
+}
+
+impl std::fmt::Display for RecordCounts {
+    /// One-line format: `local L, central C, eocd E, <consistent?>`
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "local {}, central {}, eocd {}, {}",
+            self.local_files,
+            self.central_directory_file_headers,
+            self.end_of_central_directory_records,
+            if self.is_consistent() {
+                "consistent"
+            } else {
+                "inconsistent"
+            },
+        )
+    }
+}
+
+/// Optional summary columns; see [`Zip::summary_with`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SummaryOptions {
+    /// Append the encryption method (see [`Encryption`])
+    pub encryption: bool,
+
+    /// Append the compression method name (see [`compression_name`])
+    pub method: bool,
+
+    /// Append `(exec)` to executable items, or an empty field to the others (see
+    /// [`CentralDirectoryFileHeader::is_executable`])
+    pub executable: bool,
+
+    /// Append the CRC-32 verification status (`OK`, `BAD`, or `-`; see [`Verification::status`])
+    pub verify: bool,
+
+    /// Verify compressed items too by decompressing them (see [`Zip::verify`])
+    pub deep: bool,
+
+    /// Append the compression method name, compressed size, and uncompressed size from each
+    /// item's local file header (from the data descriptor for streamed zip files; see
+    /// [`Zip::local_file_for`]), or `-` for each if it is missing, to compare with the central
+    /// directory file header
+    pub detailed: bool,
+
+    /// Precede the items with the zip file comment (see [`Zip::comment`]), each line prefixed
+    /// with `# `
+    pub comment: bool,
+
+    /// Order the items depth-first by path (each folder followed by its contents) instead of in
+    /// central directory order
+    pub tree_order: bool,
+
+    /// Include only items with at least this uncompressed size
+    pub min_size: Option<u64>,
+
+    /// Include only items with at most this uncompressed size
+    pub max_size: Option<u64>,
+
+    /// Include only items with this CRC-32 (see [`Zip::find_by_crc`])
+    pub crc32: Option<u32>,
+
+    /// Include only items with exactly this uncompressed size (see [`Zip::find_by_size`])
+    pub size: Option<u64>,
+
+    /// Field delimiter (default: tab), e.g. `\0` for data that may contain tabs
+    pub delimiter: Option<String>,
+
+    /// Record separator (default: newline), e.g. `\0` for `xargs -0`
+    pub separator: Option<String>,
+
+    /// Include only items whose name matches at least one of these glob patterns (see
+    /// [`glob_match`]); empty includes every item
+    pub filter: Vec<String>,
+
+    /// Exclude items whose name matches any of these glob patterns, even if they match
+    /// [`filter`](SummaryOptions::filter)
+    pub exclude: Vec<String>,
+
+    /// Show modified dates in UTC (with a `Z` suffix), assuming the MS-DOS date/time of each
+    /// item, which has no time zone, was recorded at this many minutes east of UTC (see
+    /// [`CentralDirectoryFileHeader::modified_utc`]); `None` shows the local date/time as stored
+    pub utc_offset: Option<i32>,
+}
+
+impl SummaryOptions {
+    /// Whether the options include an item
+    pub fn includes(&self, header: &CentralDirectoryFileHeader) -> bool {
+        let size = header.uncompressed_size() as u64;
+        self.min_size.map_or(true, |min| size >= min)
+            && self.max_size.map_or(true, |max| size <= max)
+            && self.crc32.map_or(true, |crc32| header.crc32() == crc32)
+            && self.size.map_or(true, |n| size == n)
+    }
+
+    /// Whether the [`filter`](SummaryOptions::filter) and
+    /// [`exclude`](SummaryOptions::exclude) patterns include an item name; exclude takes
+    /// precedence
+    pub fn includes_name(&self, name: &str) -> bool {
+        (self.filter.is_empty() || self.filter.iter().any(|p| glob_match(p, name)))
+            && !self.exclude.iter().any(|p| glob_match(p, name))
+    }
+
+    /// Field delimiter
+    pub fn delimiter(&self) -> &str {
+        self.delimiter.as_deref().unwrap_or("\t")
+    }
+
+    /// Record separator
+    pub fn separator(&self) -> &str {
+        self.separator.as_deref().unwrap_or("\n")
+    }
 }
 
 impl Zip {
+    /// Wrap parsed entries and record the byte offset of each one, starting at `start`, for a
+    /// zip file that starts at `base`
+    fn new(entries: Entries, base: u64, start: u64) -> Self {
+        let mut offsets = Vec::with_capacity(entries.list.len());
+        let mut offset = start;
+        for entry in &entries.list {
+            offsets.push(offset);
+            offset += entry.record_size();
+        }
+        Self {
+            path: None,
+            entries,
+            offsets,
+            base,
+            trailing: 0,
+            truncated: None,
+            encoding: Encoding::default(),
+            sha256: None,
+        }
+    }
+
+    /// Wrap already-parsed entries, with no path, a [base offset](Zip::base_offset) of 0, and
+    /// record offsets computed from the record sizes
+    ///
+    /// No validation is performed: the entries need not form a well-formed zip file (see
+    /// [`Zip::strict`] and [`Zip::audit`] to check them).
+    pub fn from_entries(entries: Vec<Entry>) -> Self {
+        Self::new(Entries { list: entries }, 0, 0)
+    }
+
     /// Process a zip file at path
     pub fn from<P>(path: P) -> Result<Self, String>
     where
         P: Into<PathBuf>,
     {
         let path: PathBuf = path.into();
-        if !path.exists() {
-            return Err(format!("Path does not exist: `{}`", path.display()));
-        } else if !path.is_file() {
-            return Err(format!("Path is not a file: `{}`", path.display()));
-        }
-        match File::open(&path) {
-            Ok(f) => {
-                let mut r = BufReader::new(f);
-                let mut zip = Zip::process(&mut r)?;
-                zip.path = Some(path);
-                Ok(zip)
-            }
-            Err(e) => Err(format!("{e}: `{}`", path.display())),
-        }
+        let mut zip = Zip::process(&mut open_file(&path)?)?;
+        zip.path = Some(path);
+        Ok(zip)
+    }
+
+    /// Set the path reported for a zip file processed from a file
+    pub(crate) fn with_path(mut self, path: PathBuf) -> Self {
+        self.path = Some(path);
+        self
+    }
+
+    /// Process a zip file embedded at byte `offset` of a larger file
+    ///
+    /// Offsets reported by the resulting [`Zip`] are absolute within the outer file; see
+    /// [`Zip::process`].
+    pub fn from_offset<R>(r: &mut R, offset: u64) -> Result<Self, String>
+    where
+        R: Read + Seek,
+    {
+        r.seek(SeekFrom::Start(offset)).map_err(|e| e.to_string())?;
+        Zip::process(&mut BufReader::new(r))
+    }
+
+    /// Process a zip file held in memory
+    ///
+    /// Never panics, whatever the bytes; malformed input is an error or, for a truncated record,
+    /// counted as trailing data (see [`Zip::process`]).
+    /// Declared lengths are checked against the input before anything is allocated for them.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        Zip::process(&mut BufReader::new(std::io::Cursor::new(bytes)))
     }
 
     /// Process the raw bytes of a zip file
+    ///
+    /// Parsing begins at the reader's current position, which becomes the
+    /// [base offset](Zip::base_offset) of the zip file.
+    ///
+    /// Parsing stops after the end of central directory record; any bytes after it are counted
+    /// as trailing data (see [`Zip::trailing_bytes`]) rather than rejected.
+    /// A record truncated by the end of the file is likewise counted as trailing data (see
+    /// [`Zip::truncated`]), unless it is the first record ("Unexpected end of file").
+    /// A record whose declared lengths run past the end of the file is an error naming the field
+    /// and entry.
+    /// If the first record can't be read but the end of central directory record locates the
+    /// first local file header further on, the error names the prefix before it, as in a
+    /// self-extracting archive; see [`Zip::from_offset`].
+    /// Local files with strong encryption (general purpose bit flag 6 or 13) are rejected
+    /// ("Strong encryption is not supported"), since the central directory may be encrypted.
+    /// Use [`Zip::process_strict`] to reject these and other nonconformances.
     pub fn process<R>(r: &mut BufReader<R>) -> Result<Self, String>
     where
         R: Read + Seek,
     {
-        match r.read_le::<Entries>() {
-            Ok(entries) => {
-                if entries.list.is_empty() {
-                    Err(String::from("Unexpected end of file"))
-                } else {
-                    Ok(Self {
-                        path: None,
-                        entries,
-                    })
+        Zip::parse(r, 0, false, u64::MAX)
+    }
+
+    /// Process the raw bytes of a damaged zip file, keeping the records parsed before the damage
+    ///
+    /// Like [`Zip::process`], but any error (an invalid signature, a truncated record, or an
+    /// empty file) stops parsing instead of failing it.
+    /// The error is available via [`Zip::truncated`], and the remaining bytes are counted as
+    /// trailing data, so the listing may be incomplete.
+    pub fn process_lenient<R>(r: &mut BufReader<R>) -> Result<Self, String>
+    where
+        R: Read + Seek,
+    {
+        Zip::parse(r, 0, true, u64::MAX)
+    }
+
+    /// Resume parsing a zip file `start_offset` bytes after the reader's current position
+    ///
+    /// Like [`Zip::process`], with the reader's current position as the
+    /// [base offset](Zip::base_offset), but the records before `start_offset` are not read, e.g.
+    /// to re-parse only the central directory at the `cd_offset` found by an earlier pass.
+    /// Only the records from `start_offset` on are available, so, for example,
+    /// [`Zip::local_file_for`] finds no local file before it.
+    pub fn process_from<R>(r: &mut BufReader<R>, start_offset: u64) -> Result<Self, String>
+    where
+        R: Read + Seek,
+    {
+        Zip::parse(r, start_offset, false, u64::MAX)
+    }
+
+    /// Parse records from `skip` bytes after the base offset until the end of central directory
+    /// record or an error; see [`Zip::process`], [`Zip::process_lenient`], and
+    /// [`Zip::process_from`]
+    ///
+    /// A record with a declared length larger than `max_alloc`, or streamed file data longer than
+    /// it, is an error; see [`ZipReader::max_alloc`].
+    pub(crate) fn parse<R>(
+        r: &mut BufReader<R>,
+        skip: u64,
+        lenient: bool,
+        max_alloc: u64,
+    ) -> Result<Self, String>
+    where
+        R: Read + Seek,
+    {
+        let io = |e: std::io::Error| e.to_string();
+        let base = r.stream_position().map_err(io)?;
+        let end = r.seek(SeekFrom::End(0)).map_err(io)?;
+        let start = base.saturating_add(skip);
+        let mut pos = r.seek(SeekFrom::Start(start)).map_err(io)?;
+        let mut list = vec![];
+        let mut truncated = None;
+        let mut previous = pos;
+
+        // Tailor the error for a common non-zip file passed by mistake
+        let mut head = vec![];
+        r.by_ref().take(262).read_to_end(&mut head).map_err(io)?;
+        r.seek(SeekFrom::Start(start)).map_err(io)?;
+        if let Some(format) = sniff_format(&head) {
+            let e = format!("This looks like a {format} file, not a zip archive");
+            if !lenient {
+                return Err(e);
+            }
+            truncated = Some(e);
+        }
+
+        while truncated.is_none() && pos < end {
+            // Check the declared lengths before reading the record, so that a forged length
+            // can't trigger an allocation larger than the file
+            let header = peek_header(r, pos, end).map_err(io)?;
+            if let Some(e) = declared_length_error(&header, pos, end, list.len()) {
+                if !lenient {
+                    return Err(e);
                 }
+                truncated = Some(e);
+                break;
             }
-            Err(e) => {
-                let e = e.root_cause(); // not the backtrace error
-
-                // Check if the outer error is an `EnumErrors`
-                if let Error::EnumErrors {
-                    pos: _,
-                    variant_errors,
-                } = &e
-                {
-                    // Count the bad magic errors and save the magic value seen
-                    let mut count_bad_magic = 0;
-                    let mut magic = None;
-                    for (_, i) in variant_errors {
-                        let i = i.root_cause();
-                        if let Error::BadMagic { pos: _, found } = &i {
-                            magic = Some(magic_hex(&format!("{:?}", found)));
-                            count_bad_magic += 1;
+            if let Some(e) = allocation_limit_error(&header, pos, list.len(), max_alloc) {
+                if !lenient {
+                    return Err(e);
+                }
+                truncated = Some(e);
+                break;
+            }
+            match r.read_le_args::<Entry>((Some(max_alloc),)) {
+                Ok(entry) => {
+                    // The strong encryption header and a masked central directory can't be
+                    // parsed; stop before they turn into invalid signatures
+                    if let Entry::LocalFile(i) = &entry {
+                        if i.has_strong_encryption_flag() || i.has_masked_header_flag() {
+                            let e = format!(
+                                "Strong encryption is not supported: `{}`",
+                                i.name(Encoding::default()),
+                            );
+                            if !lenient {
+                                return Err(e);
+                            }
+                            truncated = Some(e);
+                            break;
                         }
                     }
-
-                    // If got a magic value and all the variant errors were bad magic errors,
-                    // return an invalid signature error.
-                    if magic.is_some() && count_bad_magic == variant_errors.len() {
-                        return Err(format!("Invalid signature: `{}`", magic.as_ref().unwrap()));
+                    previous = pos;
+                    pos = r.stream_position().map_err(io)?;
+                    let eocd = matches!(entry, Entry::EndOfCentralDirectoryRecord(_));
+                    list.push(entry);
+                    if eocd {
+                        break;
                     }
                 }
-
-                // Return the error
-                Err(e.to_string())
+                Err(e) => {
+                    if e.is_eof() {
+                        truncated = Some(String::from("Unexpected end of file"));
+                        break;
+                    }
+                    let mut e = parse_error(&e);
+                    // The data descriptor flag decides how a local file is read, so a mismatch
+                    // makes the next record unreadable
+                    if let Some(Entry::LocalFile(i)) = list.last() {
+                        if let Some(finding) =
+                            i.data_descriptor_finding(previous, Encoding::default())
+                        {
+                            e = format!("{e} ({finding})");
+                        }
+                    }
+                    if list.is_empty() {
+                        if let Ok(Some(prefix)) = prefix_length(r, start) {
+                            e = format!(
+                                "{e}; the zip file starts after a {prefix}-byte prefix, likely a \
+                                self-extracting stub (parse it from offset {})",
+                                start + prefix,
+                            );
+                        }
+                    }
+                    if !lenient {
+                        return Err(e);
+                    }
+                    truncated = Some(e);
+                    break;
+                }
             }
         }
+        if list.is_empty() && !lenient {
+            return Err(truncated.unwrap_or_else(|| String::from("Unexpected end of file")));
+        }
+        if list.is_empty() && truncated.is_none() {
+            truncated = Some(String::from("Unexpected end of file"));
+        }
+        let mut zip = Self::new(Entries { list }, base, start);
+        zip.trailing = end.saturating_sub(pos);
+        zip.truncated = truncated;
+        Ok(zip)
+    }
+
+    /// Decode file names and comments with `encoding` instead of [`Encoding::Auto`]
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Encoding used to decode file names and comments
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
     }
 
     /// Helper to call `verbose()` or `summary()` based on the value of `verbose`
@@ -90,28 +454,710 @@ impl Zip {
 
     /// Generate a complete analysis of the zip file contents
     pub fn verbose(&self) -> Result<String, String> {
-        let mut s = vec![];
+        self.verbose_with("---\n")
+    }
+
+    /// Generate a complete analysis of the zip file contents with a custom record separator
+    /// instead of `---\n`
+    pub fn verbose_with(&self, separator: &str) -> Result<String, String> {
+        let mut s = self.verbose_without_eof(separator)?;
+        s.push_str(separator);
+        s.push_str("EOF\n");
+        s.push_str(separator);
+        Ok(s)
+    }
+
+    /// Like [`Zip::verbose_with`], but without the trailing `EOF` marker (separator, `EOF`,
+    /// separator), so the output ends with the last record, e.g. to concatenate the output of
+    /// several zip files
+    pub fn verbose_without_eof(&self, separator: &str) -> Result<String, String> {
+        let mut s = String::new();
         for entry in &self.entries.list {
-            s.push(String::from("---\n"));
+            s.push_str(separator);
             match entry {
-                Entry::LocalFile(i) => s.push(i.verbose()),
-                Entry::CentralDirectoryFileHeader(i) => s.push(i.verbose()),
-                Entry::EndOfCentralDirectoryRecord(i) => s.push(i.verbose()),
+                Entry::LocalFile(i) => i.write_verbose_with_encoding(&mut s, self.encoding),
+                Entry::ArchiveExtraDataRecord(i) => i.write_verbose(&mut s),
+                Entry::CentralDirectoryFileHeader(i) => {
+                    i.write_verbose_with_encoding(&mut s, self.encoding)
+                }
+                Entry::EndOfCentralDirectoryRecord(i) => {
+                    i.write_verbose_with_encoding(&mut s, self.encoding)
+                }
+            }
+        }
+        Ok(s)
+    }
+
+    /// Generate a complete analysis of the zip file contents as indented blocks
+    ///
+    /// Like [`Zip::verbose`], but each record is grouped under a header line with its name and
+    /// offset, and the parsed extra fields are nested under the raw extra field.
+    pub fn pretty(&self) -> Result<String, String> {
+        let indent = |n: usize, line: &str| format!("{}{line}\n", "    ".repeat(n));
+        let mut s = String::new();
+        for (offset, entry) in self.entries_with_offsets() {
+            let (header, verbose, extra) = match entry {
+                Entry::LocalFile(i) => (
+                    format!("Local file header `{}`", i.name(self.encoding)),
+                    i.verbose_with_encoding(self.encoding),
+                    Some(i.extra_field()),
+                ),
+                Entry::ArchiveExtraDataRecord(i) => (
+                    String::from("Archive extra data record"),
+                    i.verbose(),
+                    Some(i.extra_field()),
+                ),
+                Entry::CentralDirectoryFileHeader(i) => (
+                    format!("Central directory file header `{}`", i.name(self.encoding)),
+                    i.verbose_with_encoding(self.encoding),
+                    Some(i.extra_field()),
+                ),
+                Entry::EndOfCentralDirectoryRecord(i) => (
+                    String::from("End of central directory record"),
+                    i.verbose_with_encoding(self.encoding),
+                    None,
+                ),
+            };
+            s.push_str(&format!("{header} at offset {offset}\n"));
+            for line in verbose.lines() {
+                s.push_str(&indent(1, line));
+                if line.starts_with("extra_field = ") {
+                    if let Some(extra) = extra {
+                        match extra_fields(extra) {
+                            Ok(fields) => {
+                                for field in &fields {
+                                    for line in field.verbose().lines() {
+                                        s.push_str(&indent(2, line));
+                                    }
+                                }
+                            }
+                            Err(e) => s.push_str(&indent(2, &e)),
+                        }
+                    }
+                }
             }
         }
-        s.push(String::from("---\nEOF\n---\n"));
-        Ok(s.join(""))
+        Ok(s)
     }
 
     /// Generate a summary of the the zip file contents
     /// (file name, whether item is a folder, uncompressed size, modified date/time, and comment)
     pub fn summary(&self) -> Result<String, String> {
-        let mut s = vec![];
+        self.summary_with(&SummaryOptions::default())
+    }
+
+    /// Generate a summary of the zip file contents with optional columns
+    pub fn summary_with(&self, options: &SummaryOptions) -> Result<String, String> {
+        let mut s = String::new();
+        if options.comment {
+            if let Some(comment) = self.comment() {
+                for line in comment.lines() {
+                    s.push_str("# ");
+                    s.push_str(line);
+                    s.push_str(options.separator());
+                }
+            }
+        }
+        for row in self.summary_rows(options) {
+            s.push_str(&row);
+            s.push_str(options.separator());
+        }
+        Ok(s)
+    }
+
+    /// Generate the summary row of each included item, without the zip file comment or record
+    /// separators; see [`Zip::summary_with`]
+    pub fn summary_rows(&self, options: &SummaryOptions) -> Vec<String> {
+        let verifications = if options.verify {
+            self.verify(options.deep)
+        } else {
+            vec![]
+        };
+        let mut headers = self.central_directory().enumerate().collect::<Vec<_>>();
+        if options.tree_order {
+            // Sort on path components so children immediately follow their parent folder
+            headers.sort_by_cached_key(|(_, i)| {
+                i.name(self.encoding)
+                    .trim_end_matches('/')
+                    .split('/')
+                    .map(String::from)
+                    .collect::<Vec<_>>()
+            });
+        }
+        let mut rows = vec![];
+        for (n, i) in headers {
+            let name = i.name(self.encoding);
+            if options.includes(i) && options.includes_name(&name) {
+                let mut row = String::new();
+                i.write_summary_named(&mut row, &name, self.encoding, options);
+                row.truncate(row.len() - options.separator().len());
+                if let Some(verification) = verifications.get(n) {
+                    row.push_str(options.delimiter());
+                    row.push_str(verification.status());
+                }
+                if options.detailed {
+                    let d = options.delimiter();
+                    match self.local_file_for(i) {
+                        Some(local) => write!(
+                            row,
+                            "{d}{}{d}{}{d}{}",
+                            compression_name(local.compression()),
+                            local.compressed_size(),
+                            local.uncompressed_size(),
+                        )
+                        .unwrap(),
+                        None => write!(row, "{d}-{d}-{d}-").unwrap(),
+                    }
+                }
+                rows.push(row);
+            }
+        }
+        rows
+    }
+
+    /// Generate a summary of the items whose uncompressed size is within `min..=max` (either bound
+    /// is optional)
+    pub fn summary_size_range(&self, min: Option<u64>, max: Option<u64>) -> Result<String, String> {
+        self.summary_with(&SummaryOptions {
+            min_size: min,
+            max_size: max,
+            ..Default::default()
+        })
+    }
+
+    /// Generate a summary of the items whose name does not match any of the glob `patterns`
+    /// (see [`glob_match`]), e.g. `node_modules/**`
+    pub fn summary_excluding(&self, patterns: &[&str]) -> Result<String, String> {
+        self.summary_with(&SummaryOptions {
+            exclude: patterns.iter().map(|p| p.to_string()).collect(),
+            ..Default::default()
+        })
+    }
+
+    /// Generate a machine-readable manifest of the zip file contents
+    ///
+    /// The schema is stable: a header line followed by one tab-separated line per central
+    /// directory file header with the file name, absolute local file header offset, compressed
+    /// size, uncompressed size, CRC-32, and compression method, all numbers in plain decimal.
+    pub fn manifest(&self) -> String {
+        let mut s = vec![String::from(
+            "name\toffset\tcompressed_size\tuncompressed_size\tcrc32\tmethod\n",
+        )];
+        for i in self.central_directory() {
+            s.push(format!(
+                "{}\t{}\t{}\t{}\t{}\t{}\n",
+                i.name(self.encoding),
+                self.base + i.lfh_offset() as u64,
+                i.compressed_size(),
+                i.uncompressed_size(),
+                i.crc32(),
+                i.compression(),
+            ));
+        }
+        s.join("")
+    }
+
+    /// Absolute byte offset and length of the compressed file data of each file
+    ///
+    /// The offset is computed from `lfh_offset` and the local file header (whose file name and
+    /// extra field lengths may differ from the central directory file header's), and the length
+    /// is the compressed size.
+    /// Folders and files without a local file header are omitted.
+    pub fn data_ranges(&self) -> Vec<(String, u64, u64)> {
+        self.central_directory()
+            .filter(|i| !i.is_dir())
+            .filter_map(|i| {
+                let local = self.local_file_for(i)?;
+                Some((
+                    i.name(self.encoding).into_owned(),
+                    self.base + i.lfh_offset() as u64 + local.data_offset(),
+                    i.compressed_size() as u64,
+                ))
+            })
+            .collect()
+    }
+
+    /// Generate a tab-separated list of the compressed data ranges: file name, absolute offset,
+    /// and length; see [`Zip::data_ranges`]
+    pub fn ranges(&self) -> String {
+        self.data_ranges()
+            .iter()
+            .map(|(name, offset, length)| format!("{name}\t{offset}\t{length}\n"))
+            .collect()
+    }
+
+    /// Generate a JSON array with an object per central directory file header
+    ///
+    /// Each object has the `name`, `is_dir`, `offset` (absolute local file header offset),
+    /// `compressed_size`, `uncompressed_size`, `crc32` (decimal), `method`, `modified`, and
+    /// `comment`.
+    /// See [`Json::to_json`] for `pretty`.
+    pub fn json(&self, pretty: bool) -> String {
+        let items = self
+            .central_directory()
+            .map(|i| {
+                Json::object([
+                    ("name", Json::String(i.name(self.encoding).into_owned())),
+                    ("is_dir", Json::Bool(i.is_dir())),
+                    ("offset", Json::Number(self.base + i.lfh_offset() as u64)),
+                    ("compressed_size", Json::Number(i.compressed_size() as u64)),
+                    (
+                        "uncompressed_size",
+                        Json::Number(i.uncompressed_size() as u64),
+                    ),
+                    ("crc32", Json::Number(i.crc32() as u64)),
+                    ("method", Json::Number(i.compression() as u64)),
+                    ("modified", Json::String(i.modified())),
+                    (
+                        "comment",
+                        Json::String(i.comment(self.encoding).into_owned()),
+                    ),
+                ])
+            })
+            .collect();
+        Json::Array(items).to_json(pretty)
+    }
+
+    /// Generate a JSON object mapping each item name to where its records are, for tools that
+    /// patch zip files in place
+    ///
+    /// Each value has the `local_header_offset`, `data_offset` (file data), `data_length`
+    /// (compressed size), and `central_header_offset`.
+    /// Offsets are absolute within the file the zip file was read from, so they include the
+    /// [base offset](Zip::base_offset) of a zip file read with [`Zip::from_offset`] or after a
+    /// prefix.
+    /// `data_offset` and `data_length` are `null` if the local file header is missing.
+    /// Items are in central directory order; a duplicate name is repeated as is, and most JSON
+    /// parsers keep the last one.
+    pub fn layout_json(&self) -> String {
+        let items = self
+            .entries_with_offsets()
+            .filter_map(|(offset, entry)| match entry {
+                Entry::CentralDirectoryFileHeader(i) => Some((offset, i)),
+                _ => None,
+            })
+            .map(|(central, i)| {
+                let local = self.base + i.lfh_offset() as u64;
+                let (data_offset, data_length) = match self.local_file_for(i) {
+                    Some(f) => (
+                        Json::Number(local + f.data_offset()),
+                        Json::Number(i.compressed_size() as u64),
+                    ),
+                    None => (Json::Null, Json::Null),
+                };
+                let layout = Json::object([
+                    ("local_header_offset", Json::Number(local)),
+                    ("data_offset", data_offset),
+                    ("data_length", data_length),
+                    ("central_header_offset", Json::Number(central)),
+                ]);
+                (i.name(self.encoding).into_owned(), layout)
+            })
+            .collect();
+        Json::Object(items).to_json(false)
+    }
+
+    /// All parsed entries in parse order, which is the order the records appear in the file
+    /// (local files, then central directory file headers, then the end of central directory
+    /// record for a well-formed zip file)
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries.list
+    }
+
+    /// Parsed entries paired with the absolute byte offset where each one starts
+    pub fn entries_with_offsets(&self) -> impl Iterator<Item = (u64, &Entry)> {
+        self.offsets.iter().copied().zip(&self.entries.list)
+    }
+
+    /// Byte offset where the zip file starts within the file it was read from
+    ///
+    /// Offsets stored in the zip file (`lfh_offset`, `cd_offset`) are relative to this offset,
+    /// while offsets reported by [`Zip`] methods are absolute.
+    pub fn base_offset(&self) -> u64 {
+        self.base
+    }
+
+    /// Number of bytes after the end of central directory record (or after the last complete
+    /// record) that were not parsed
+    pub fn trailing_bytes(&self) -> u64 {
+        self.trailing
+    }
+
+    /// Error that stopped parsing before the end of the file, if any, in which case the listing
+    /// may be incomplete; see [`Zip::process_lenient`]
+    pub fn truncated(&self) -> Option<&str> {
+        self.truncated.as_deref()
+    }
+
+    /// SHA-256 of the bytes the zip file was read from in lowercase hex, if it was read with
+    /// [`ZipReader::hash`]
+    pub fn sha256(&self) -> Option<&str> {
+        self.sha256.as_deref()
+    }
+
+    /// End of central directory record, if present
+    pub fn end_of_central_directory(&self) -> Option<&EndOfCentralDirectoryRecord> {
+        self.entries.list.iter().find_map(|entry| match entry {
+            Entry::EndOfCentralDirectoryRecord(i) => Some(i),
+            _ => None,
+        })
+    }
+
+    /// Zip file comment from the end of central directory record, if any and not empty
+    pub fn comment(&self) -> Option<Cow<'_, str>> {
+        self.end_of_central_directory()
+            .map(|i| i.comment(self.encoding))
+            .filter(|comment| !comment.is_empty())
+    }
+
+    /// Local file whose header starts at byte `offset`
+    pub fn local_file_at(&self, offset: u64) -> Option<&LocalFile> {
+        match self.offsets.binary_search(&offset) {
+            Ok(n) => match &self.entries.list[n] {
+                Entry::LocalFile(i) => Some(i),
+                _ => None,
+            },
+            Err(_) => None,
+        }
+    }
+
+    /// Local file that a central directory file header points to
+    pub fn local_file_for(&self, header: &CentralDirectoryFileHeader) -> Option<&LocalFile> {
+        self.local_file_at(self.base + header.lfh_offset() as u64)
+    }
+
+    /// Decoded file names of the central directory file headers, in order
+    pub fn names(&self) -> Vec<String> {
+        self.central_directory()
+            .map(|i| i.name(self.encoding).into_owned())
+            .collect()
+    }
+
+    /// Decoded file names paired with their central directory file headers
+    ///
+    /// Unlike a map keyed by name, every occurrence of a duplicate name is kept, so entries
+    /// shadowed by a later entry with the same name remain visible.
+    /// The order is central directory order.
+    pub fn entries_by_name(&self) -> Vec<(String, &CentralDirectoryFileHeader)> {
+        self.central_directory()
+            .map(|i| (i.name(self.encoding).into_owned(), i))
+            .collect()
+    }
+
+    /// Central directory file header of the first item named `name`
+    pub fn find(&self, name: &str) -> Option<&CentralDirectoryFileHeader> {
+        self.central_directory()
+            .find(|i| i.name(self.encoding) == name)
+    }
+
+    /// Central directory file headers of the items with CRC-32 `crc32`
+    pub fn find_by_crc(&self, crc32: u32) -> Vec<&CentralDirectoryFileHeader> {
+        self.central_directory()
+            .filter(|i| i.crc32() == crc32)
+            .collect()
+    }
+
+    /// Central directory file headers of the items with uncompressed size `size`
+    pub fn find_by_size(&self, size: u64) -> Vec<&CentralDirectoryFileHeader> {
+        self.central_directory()
+            .filter(|i| i.uncompressed_size() as u64 == size)
+            .collect()
+    }
+
+    /// Central directory file headers of the `n` largest files by uncompressed size, largest
+    /// first; files of the same size keep their central directory order
+    pub fn largest(&self, n: usize) -> Vec<&CentralDirectoryFileHeader> {
+        let mut files = self
+            .central_directory()
+            .filter(|i| !i.is_dir())
+            .collect::<Vec<_>>();
+        files.sort_by_key(|i| std::cmp::Reverse(i.uncompressed_size()));
+        files.truncate(n);
+        files
+    }
+
+    /// Iterate the central directory file headers
+    pub fn central_directory(&self) -> impl Iterator<Item = &CentralDirectoryFileHeader> {
+        self.entries.list.iter().filter_map(|entry| match entry {
+            Entry::CentralDirectoryFileHeader(i) => Some(i),
+            _ => None,
+        })
+    }
+
+    /// Compute summary statistics (file and folder counts, total sizes, encryption) from the
+    /// central directory file headers
+    pub fn stats(&self) -> ZipStats {
+        let mut stats = ZipStats::default();
+        for i in self.central_directory() {
+            if i.is_dir() {
+                stats.dirs += 1;
+            } else {
+                stats.files += 1;
+            }
+            if i.is_encrypted() {
+                stats.encrypted += 1;
+            }
+            stats.compressed_size += i.compressed_size() as u64;
+            stats.uncompressed_size += i.uncompressed_size() as u64;
+        }
+        stats
+    }
+
+    /// Count the parsed records of each type
+    ///
+    /// A healthy zip file has one central directory file header per local file and exactly one
+    /// end of central directory record; see [`RecordCounts::is_consistent`].
+    pub fn counts(&self) -> RecordCounts {
+        let mut counts = RecordCounts::default();
         for entry in &self.entries.list {
-            if let Entry::CentralDirectoryFileHeader(i) = entry {
-                s.push(i.summary());
+            match entry {
+                Entry::LocalFile(_) => counts.local_files += 1,
+                Entry::ArchiveExtraDataRecord(_) => counts.archive_extra_data_records += 1,
+                Entry::CentralDirectoryFileHeader(_) => counts.central_directory_file_headers += 1,
+                Entry::EndOfCentralDirectoryRecord(_) => {
+                    counts.end_of_central_directory_records += 1
+                }
+            }
+        }
+        counts
+    }
+
+    /// Whether the zip file looks like it was written in a streaming fashion
+    ///
+    /// True if any local file header leaves its sizes to a data descriptor (see
+    /// [`LocalFile::is_streamed`]), or if the end of central directory record's `cd_offset` does
+    /// not match where the central directory actually starts.
+    /// Such zip files may need a linear scan rather than random access via the central
+    /// directory.
+    pub fn is_streamed(&self) -> bool {
+        let zeroed = self.entries.list.iter().any(|entry| match entry {
+            Entry::LocalFile(i) => i.is_streamed(),
+            _ => false,
+        });
+        let misaligned = match (self.end_of_central_directory(), self.cd_start()) {
+            (Some(eocd), Some(start)) => eocd.cd_offset() as u64 != start,
+            _ => false,
+        };
+        zeroed || misaligned
+    }
+
+    /// Offset of the first central directory file header (or of the end of central directory
+    /// record if there are none) relative to the [base offset](Zip::base_offset)
+    pub(crate) fn cd_start(&self) -> Option<u64> {
+        self.entries_with_offsets()
+            .find(|(_, entry)| {
+                matches!(
+                    entry,
+                    Entry::CentralDirectoryFileHeader(_) | Entry::EndOfCentralDirectoryRecord(_)
+                )
+            })
+            .map(|(offset, _)| offset - self.base)
+    }
+
+    /// Compute the total compressed size, total uncompressed size, and percentage saved by
+    /// compression from the central directory file headers; see [`savings_percent`]
+    pub fn total_savings(&self) -> (u64, u64, f64) {
+        let stats = self.stats();
+        (
+            stats.compressed_size,
+            stats.uncompressed_size,
+            savings_percent(stats.compressed_size, stats.uncompressed_size),
+        )
+    }
+
+    /// Folder paths with a trailing `/` that only appear in the names of the items they contain
+    /// and have no entry of their own, as in zip files that list only files (`a/b/c.txt`)
+    pub fn implied_directories(&self) -> BTreeSet<String> {
+        let explicit = self
+            .central_directory()
+            .filter(|i| i.is_dir())
+            .map(|i| i.name(self.encoding).into_owned())
+            .collect::<BTreeSet<_>>();
+        let mut implied = BTreeSet::new();
+        for i in self.central_directory() {
+            let name = i.name(self.encoding);
+            for (n, _) in name.match_indices('/') {
+                let folder = &name[..=n];
+                if !explicit.contains(folder) {
+                    implied.insert(folder.to_string());
+                }
+            }
+        }
+        implied
+    }
+
+    /// Sum the uncompressed sizes of the files under each folder, recursively
+    ///
+    /// Keys are folder paths with a trailing `/`, including implicit folders that only appear in
+    /// the names of the items they contain and have no entry of their own.
+    pub fn directory_sizes(&self) -> BTreeMap<String, u64> {
+        let mut sizes = BTreeMap::new();
+        for i in self.central_directory() {
+            let name = i.name(self.encoding);
+            let size = if i.is_dir() {
+                0
+            } else {
+                i.uncompressed_size() as u64
+            };
+            for (n, _) in name.match_indices('/') {
+                *sizes.entry(name[..=n].to_string()).or_insert(0) += size;
+            }
+        }
+        sizes
+    }
+}
+
+/// Name, fixed size, and declared variable-length fields (in order) of the record whose
+/// fixed-size part is `b` (see [`peek_header`])
+///
+/// Returns `None` if the record type is unknown or the fixed-size part itself is truncated.
+fn declared_lengths(b: &[u8]) -> Option<(&'static str, u64, Vec<(&'static str, u64)>)> {
+    let u16_at = |n: usize| {
+        b.get(n..n + 2)
+            .map(|x| u16::from_le_bytes([x[0], x[1]]) as u64)
+    };
+    let u32_at = |n: usize| {
+        b.get(n..n + 4)
+            .map(|x| u32::from_le_bytes([x[0], x[1], x[2], x[3]]) as u64)
+    };
+    Some(match b.get(..4)? {
+        b"PK\x03\x04" => {
+            let (file_name_length, extra_field_length) = local_header_lengths(b)?;
+            (
+                "Local file header",
+                30,
+                vec![
+                    ("file_name_length", file_name_length as u64),
+                    ("extra_field_length", extra_field_length as u64),
+                    ("compressed_size", u32_at(18)?),
+                ],
+            )
+        }
+        b"PK\x06\x08" => (
+            "Archive extra data record",
+            8,
+            vec![("extra_field_length", u32_at(4)?)],
+        ),
+        b"PK\x01\x02" => (
+            "Central directory file header",
+            46,
+            vec![
+                ("file_name_length", u16_at(28)?),
+                ("extra_field_length", u16_at(30)?),
+                ("file_comment_length", u16_at(32)?),
+            ],
+        ),
+        b"PK\x05\x06" => (
+            "End of central directory record",
+            22,
+            vec![("comment_length", u16_at(20)?)],
+        ),
+        _ => return None,
+    })
+}
+
+/// Explain a record truncated by the end of the file when one of its declared lengths is larger
+/// than the remaining bytes
+///
+/// Reads the fixed-size part `b` of the record at `pos` (see [`declared_lengths`]) and adds up
+/// its variable-length fields in order, naming the first field that runs past `end`.
+fn declared_length_error(b: &[u8], pos: u64, end: u64, index: usize) -> Option<String> {
+    let (record, fixed, fields) = declared_lengths(b)?;
+    let mut remaining = (end - pos).checked_sub(fixed)?;
+    for (field, length) in fields {
+        if length > remaining {
+            return Some(format!(
+                "{record} (entry {index}) at offset {pos}: {field} ({length}) exceeds the \
+                {remaining} remaining bytes",
+            ));
+        }
+        remaining -= length;
+    }
+    None
+}
+
+/// Explain a record with a declared length larger than `max_alloc` bytes; see
+/// [`ZipReader::max_alloc`]
+fn allocation_limit_error(b: &[u8], pos: u64, index: usize, max_alloc: u64) -> Option<String> {
+    let (record, _, fields) = declared_lengths(b)?;
+    let (field, length) = fields.into_iter().find(|(_, length)| *length > max_alloc)?;
+    Some(format!(
+        "{record} (entry {index}) at offset {pos}: {field} ({length}) exceeds the allocation \
+        limit of {max_alloc} bytes",
+    ))
+}
+
+/// Read up to the first 46 bytes (the largest fixed-size record part) of the record at the
+/// current position `pos` without moving it
+///
+/// Uses the read buffer if it holds enough bytes, so most records don't cost a seek.
+fn peek_header<R>(r: &mut BufReader<R>, pos: u64, end: u64) -> std::io::Result<Vec<u8>>
+where
+    R: Read + Seek,
+{
+    let want = (end - pos).min(46) as usize;
+    let buf = r.fill_buf()?;
+    if buf.len() >= want {
+        return Ok(buf[..want].to_vec());
+    }
+    let mut b = vec![];
+    r.by_ref().take(want as u64).read_to_end(&mut b)?;
+    r.seek(SeekFrom::Start(pos))?;
+    Ok(b)
+}
+
+/// Convert a binrw error into an error message
+fn parse_error(e: &Error) -> String {
+    let e = e.root_cause(); // not the backtrace error
+
+    // Check if the outer error is an `EnumErrors`
+    if let Error::EnumErrors {
+        pos: _,
+        variant_errors,
+    } = &e
+    {
+        // Count the bad magic errors and save the magic value seen
+        let mut count_bad_magic = 0;
+        let mut magic = None;
+        for (_, i) in variant_errors {
+            let i = i.root_cause();
+            if let Error::BadMagic { pos: _, found } = &i {
+                magic = Some(magic_hex(&format!("{:?}", found)));
+                count_bad_magic += 1;
+            }
+        }
+
+        // If got a magic value and all the variant errors were bad magic errors,
+        // return an invalid signature error.
+        if let Some(magic) = magic {
+            if count_bad_magic == variant_errors.len() {
+                return format!("Invalid signature: `{magic}`");
+            }
+        }
+
+        // If only one variant got past its magic value and failed a check (e.g. the allocation
+        // limit of a streamed local file), return why.
+        if count_bad_magic + 1 == variant_errors.len() {
+            for (_, i) in variant_errors {
+                if let Error::AssertFail { pos: _, message } = &i.root_cause() {
+                    return message.clone();
+                }
             }
         }
-        Ok(s.join(""))
+    }
+
+    e.to_string()
+}
+
+/// Open a zip file at path for [`Zip::from`] and similar constructors
+pub(crate) fn open_file(path: &Path) -> Result<BufReader<File>, String> {
+    if !path.exists() {
+        return Err(format!("Path does not exist: `{}`", path.display()));
+    } else if !path.is_file() {
+        return Err(format!("Path is not a file: `{}`", path.display()));
+    }
+    match File::open(path) {
+        Ok(f) => Ok(BufReader::new(f)),
+        Err(e) => Err(format!("{e}: `{}`", path.display())),
     }
 }